@@ -1,3 +1,4 @@
+#![cfg(feature = "nightly")]
 #![feature(test)]
 
 extern crate test;
@@ -64,3 +65,79 @@ fn read_unsigned(b: &mut test::Bencher) {
         }
     });
 }
+
+#[bench]
+fn read_signed_single_byte(b: &mut test::Bencher) {
+    let mut buf = [0; 4096];
+
+    {
+        let mut writable = &mut buf[..];
+        for i in 0..4096 {
+            leb128::write::signed(&mut writable, i % 128 - 64).unwrap();
+        }
+    }
+
+    b.iter(|| {
+        let mut readable = &buf[..];
+        for _ in 0..4096 {
+            test::black_box(leb128::read::signed(&mut readable).unwrap());
+        }
+    });
+}
+
+#[bench]
+fn read_unsigned_single_byte(b: &mut test::Bencher) {
+    let mut buf = [0; 4096];
+
+    {
+        let mut writable = &mut buf[..];
+        for i in 0..4096 {
+            leb128::write::unsigned(&mut writable, i % 128).unwrap();
+        }
+    }
+
+    b.iter(|| {
+        let mut readable = &buf[..];
+        for _ in 0..4096 {
+            test::black_box(leb128::read::unsigned(&mut readable).unwrap());
+        }
+    });
+}
+
+#[bench]
+fn read_unsigned_tabled_single_byte(b: &mut test::Bencher) {
+    let mut buf = [0; 4096];
+
+    {
+        let mut writable = &mut buf[..];
+        for i in 0..4096 {
+            leb128::write::unsigned(&mut writable, i % 128).unwrap();
+        }
+    }
+
+    b.iter(|| {
+        let mut readable = &buf[..];
+        for _ in 0..4096 {
+            test::black_box(leb128::read::unsigned_tabled(&mut readable).unwrap());
+        }
+    });
+}
+
+#[bench]
+fn read_unsigned_tabled(b: &mut test::Bencher) {
+    let mut buf = [0; 4096];
+
+    {
+        let mut writable = &mut buf[..];
+        for i in 0..2050 {
+            leb128::write::unsigned(&mut writable, i).unwrap();
+        }
+    }
+
+    b.iter(|| {
+        let mut readable = &buf[..];
+        for _ in 0..2050 {
+            test::black_box(leb128::read::unsigned_tabled(&mut readable).unwrap());
+        }
+    });
+}
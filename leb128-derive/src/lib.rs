@@ -0,0 +1,159 @@
+//! Implementation of `#[derive(Leb128)]`. Use it through the `derive` feature
+//! of the `leb128` crate rather than depending on this crate directly.
+
+#![deny(missing_docs)]
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, Index, Type};
+
+/// The codec used for a single field.
+enum Codec {
+    Unsigned,
+    Signed,
+    Zigzag,
+}
+
+/// Derive `encode` and `decode` methods that write and read each field of a
+/// struct as a LEB128 encoded integer, in declaration order.
+///
+/// Unsigned integer fields use the unsigned encoding and signed integer
+/// fields use the signed encoding. Annotate a signed field with
+/// `#[leb128(zigzag)]` to use the zigzag encoding instead.
+#[proc_macro_derive(Leb128, attributes(leb128))]
+pub fn derive_leb128(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match input.data {
+        Data::Struct(ref data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(Span::call_site(),
+                                       "#[derive(Leb128)] is only supported on structs"))
+        }
+    };
+
+    let mut encodes = vec![];
+    let mut decodes = vec![];
+    for (i, field) in fields.iter().enumerate() {
+        let codec = codec(field)?;
+        let ty = &field.ty;
+        let member = match field.ident {
+            Some(ref ident) => quote!(#ident),
+            None => {
+                let index = Index::from(i);
+                quote!(#index)
+            }
+        };
+
+        encodes.push(match codec {
+            Codec::Unsigned => quote! {
+                bytes_written += ::leb128::write::unsigned(w, self.#member as u64)?;
+            },
+            Codec::Signed => quote! {
+                bytes_written += ::leb128::write::signed(w, self.#member as i64)?;
+            },
+            Codec::Zigzag => quote! {
+                bytes_written += ::leb128::write::zigzag(w, self.#member as i64)?;
+            },
+        });
+
+        let read = match codec {
+            Codec::Unsigned => quote!(::leb128::read::unsigned(r)?),
+            Codec::Signed => quote!(::leb128::read::signed(r)?),
+            Codec::Zigzag => quote!(::leb128::read::zigzag(r)?),
+        };
+        let decode = quote! {
+            <#ty as ::std::convert::TryFrom<_>>::try_from(#read)
+                .map_err(|_| ::leb128::read::Error::Overflow)?
+        };
+        decodes.push(match field.ident {
+            Some(ref ident) => quote!(#ident: #decode),
+            None => decode,
+        });
+    }
+
+    let construct = match *fields {
+        Fields::Named(_) => quote!(Self { #(#decodes,)* }),
+        Fields::Unnamed(_) => quote!(Self(#(#decodes,)*)),
+        Fields::Unit => quote!(Self),
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Write each field as a LEB128 encoded integer, in declaration
+            /// order. Returns the number of bytes written to `w`, or an error
+            /// if writing failed.
+            #[allow(unused_variables, unused_mut)]
+            pub fn encode<W>(&self, w: &mut W) -> ::std::result::Result<usize, ::std::io::Error>
+                where W: ?Sized + ::std::io::Write
+            {
+                let mut bytes_written = 0;
+                #(#encodes)*
+                Ok(bytes_written)
+            }
+
+            /// Read each field as a LEB128 encoded integer, in declaration
+            /// order, or return an error if reading failed.
+            #[allow(unused_variables)]
+            pub fn decode<R>(r: &mut R) -> ::std::result::Result<Self, ::leb128::read::Error>
+                where R: ?Sized + ::std::io::Read
+            {
+                Ok(#construct)
+            }
+        }
+    })
+}
+
+fn codec(field: &syn::Field) -> syn::Result<Codec> {
+    let mut zigzag = false;
+    for attr in &field.attrs {
+        if attr.path().is_ident("leb128") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("zigzag") {
+                    zigzag = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown leb128 attribute"))
+                }
+            })?;
+        }
+    }
+
+    let ident = match field.ty {
+        Type::Path(ref path) if path.qself.is_none() => path.path.get_ident(),
+        _ => None,
+    };
+    let signed = match ident.map(|i| i.to_string()) {
+        Some(ref s) if ["u8", "u16", "u32", "u64", "usize"].contains(&&s[..]) => false,
+        Some(ref s) if ["i8", "i16", "i32", "i64", "isize"].contains(&&s[..]) => true,
+        _ => {
+            return Err(syn::Error::new_spanned(&field.ty,
+                                               "#[derive(Leb128)] fields must be primitive \
+                                                integers of at most 64 bits"))
+        }
+    };
+
+    match (signed, zigzag) {
+        (false, false) => Ok(Codec::Unsigned),
+        (true, false) => Ok(Codec::Signed),
+        (true, true) => Ok(Codec::Zigzag),
+        (false, true) => {
+            Err(syn::Error::new_spanned(&field.ty,
+                                        "#[leb128(zigzag)] is only supported on signed fields"))
+        }
+    }
+}
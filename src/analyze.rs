@@ -0,0 +1,136 @@
+//! Heuristics for reverse engineering data of unknown layout.
+
+use read;
+use write;
+
+/// An interpretation of a LEB128 number.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpretation {
+    /// The number is unsigned.
+    Unsigned,
+    /// The number is signed.
+    Signed,
+}
+
+/// What the bytes at the start of a buffer decode to, as returned by
+/// `classify`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Classification {
+    /// The number of bytes of the first number, or `None` if the buffer does
+    /// not start with a complete number.
+    pub len: Option<usize>,
+    /// The first number decoded as unsigned, or `None` if that fails.
+    pub unsigned: Option<u64>,
+    /// The first number decoded as signed, or `None` if that fails.
+    pub signed: Option<i64>,
+    /// Whether the first number is the canonical, shortest unsigned encoding
+    /// of its value.
+    pub canonical_unsigned: bool,
+    /// Whether the first number is the canonical, shortest signed encoding of
+    /// its value.
+    pub canonical_signed: bool,
+}
+
+impl Classification {
+    /// Whether the buffer holds exactly one number and nothing else.
+    pub fn is_single_value(&self, buf: &[u8]) -> bool {
+        self.len == Some(buf.len())
+    }
+
+    /// Guess which interpretation of the number is more plausible, or return
+    /// `None` if neither decodes.
+    ///
+    /// Canonical encodings are preferred, then the interpretation with the
+    /// smaller magnitude, since numbers in real formats tend to be small. Ties
+    /// go to unsigned. This is only a heuristic.
+    pub fn likely(&self) -> Option<Interpretation> {
+        match (self.unsigned, self.signed) {
+            (None, None) => None,
+            (Some(_), None) => Some(Interpretation::Unsigned),
+            (None, Some(_)) => Some(Interpretation::Signed),
+            (Some(u), Some(s)) => {
+                let unsigned_key = (!self.canonical_unsigned, u);
+                let signed_key = (!self.canonical_signed, s.unsigned_abs());
+                if signed_key < unsigned_key {
+                    Some(Interpretation::Signed)
+                } else {
+                    Some(Interpretation::Unsigned)
+                }
+            }
+        }
+    }
+}
+
+/// Decode the LEB128 number at the start of `buf` both as unsigned and as
+/// signed, and report both candidates along with its length.
+///
+/// ```
+/// use leb128::analyze::{classify, Interpretation};
+///
+/// let c = classify(&[0x7f]);
+/// assert_eq!((c.unsigned, c.signed), (Some(127), Some(-1)));
+/// assert_eq!(c.likely(), Some(Interpretation::Signed));
+/// ```
+pub fn classify(buf: &[u8]) -> Classification {
+    let len = buf.iter().position(|byte| byte & ::CONTINUATION_BIT == 0).map(|last| last + 1);
+    let field = &buf[..len.unwrap_or(0)];
+
+    let unsigned = read::unsigned(&mut &field[..]).ok();
+    let signed = read::signed(&mut &field[..]).ok();
+    Classification {
+        len,
+        unsigned,
+        signed,
+        canonical_unsigned: unsigned.is_some_and(|val| write::unsigned_len(val) == field.len()),
+        canonical_signed: signed.is_some_and(|val| write::signed_len(val) == field.len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let c = classify(&[0x02, 0xff]);
+        assert_eq!(c,
+                   Classification {
+                       len: Some(1),
+                       unsigned: Some(2),
+                       signed: Some(2),
+                       canonical_unsigned: true,
+                       canonical_signed: true,
+                   });
+        assert!(!c.is_single_value(&[0x02, 0xff]));
+        assert!(c.is_single_value(&[0x02]));
+        assert_eq!(c.likely(), Some(Interpretation::Unsigned));
+
+        // 64 needs a padding byte as signed, but not as unsigned.
+        let c = classify(&[0x40]);
+        assert_eq!((c.unsigned, c.signed), (Some(64), Some(-64)));
+        assert_eq!(c.likely(), Some(Interpretation::Unsigned));
+        let c = classify(&[0xc0, 0x00]);
+        assert_eq!((c.unsigned, c.signed), (Some(64), Some(64)));
+        assert!(!c.canonical_unsigned && c.canonical_signed);
+        assert_eq!(c.likely(), Some(Interpretation::Signed));
+
+        let c = classify(&[0x80, 0x7f]);
+        assert_eq!((c.unsigned, c.signed), (Some(16256), Some(-128)));
+        assert_eq!(c.likely(), Some(Interpretation::Signed));
+    }
+
+    #[test]
+    fn test_classify_invalid() {
+        let c = classify(&[0x80]);
+        assert_eq!(c.len, None);
+        assert_eq!((c.unsigned, c.signed), (None, None));
+        assert_eq!(c.likely(), None);
+
+        // Valid as signed, but too large as unsigned.
+        let buf = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f];
+        let c = classify(&buf);
+        assert_eq!(c.len, Some(10));
+        assert_eq!((c.unsigned, c.signed), (None, Some(i64::MIN)));
+        assert_eq!(c.likely(), Some(Interpretation::Signed));
+    }
+}
@@ -0,0 +1,70 @@
+//! Generators of LEB128 encoded inputs for structured fuzzing with the
+//! `arbitrary` crate.
+//!
+//! ```
+//! extern crate arbitrary;
+//! extern crate leb128;
+//!
+//! # fn main() {
+//! let mut u = arbitrary::Unstructured::new(&[1, 2, 3, 4, 5, 6, 7, 8]);
+//! let (val, bytes) = leb128::arbitrary::valid_unsigned(&mut u).unwrap();
+//! assert_eq!(leb128::read::unsigned(&mut &bytes[..]).unwrap(), val);
+//! # }
+//! ```
+
+use arbitrary_crate::{Arbitrary, Result, Unstructured};
+use write;
+
+/// Generate an arbitrary unsigned number and its canonical LEB128 encoding.
+pub fn valid_unsigned(u: &mut Unstructured) -> Result<(u64, Vec<u8>)> {
+    let val = u64::arbitrary(u)?;
+    let mut bytes = vec![];
+    write::unsigned(&mut bytes, val).expect("writing to a Vec cannot fail");
+    Ok((val, bytes))
+}
+
+/// Generate an arbitrary signed number and its canonical LEB128 encoding.
+pub fn valid_signed(u: &mut Unstructured) -> Result<(i64, Vec<u8>)> {
+    let val = i64::arbitrary(u)?;
+    let mut bytes = vec![];
+    write::signed(&mut bytes, val).expect("writing to a Vec cannot fail");
+    Ok((val, bytes))
+}
+
+/// Generate an arbitrary unsigned number and an encoding of it that is padded
+/// with at least one redundant byte, up to the ten bytes a 64-bit number may
+/// occupy. Decoders must accept these encodings.
+///
+/// The number is below `2^63`, so that its canonical encoding takes at most
+/// nine bytes and there is always room to pad it.
+pub fn overlong_unsigned(u: &mut Unstructured) -> Result<(u64, Vec<u8>)> {
+    let val = u64::arbitrary(u)? >> 1;
+    let min_bytes = u.int_in_range(write::unsigned_len(val) + 1..=10)?;
+    let mut bytes = vec![];
+    write::unsigned_min_bytes(&mut bytes, val, min_bytes).expect("writing to a Vec cannot fail");
+    Ok((val, bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use read;
+    use write;
+
+    #[test]
+    fn test_generators_round_trip() {
+        let data: Vec<u8> = (0..=255).cycle().take(4096).collect();
+        let mut u = Unstructured::new(&data);
+        while !u.is_empty() {
+            let (val, bytes) = valid_unsigned(&mut u).expect("Should generate input");
+            assert_eq!(read::unsigned(&mut &bytes[..]).expect("Should read number"), val);
+
+            let (val, bytes) = valid_signed(&mut u).expect("Should generate input");
+            assert_eq!(read::signed(&mut &bytes[..]).expect("Should read number"), val);
+
+            let (val, bytes) = overlong_unsigned(&mut u).expect("Should generate input");
+            assert!(bytes.len() > write::unsigned_len(val) && bytes.len() <= 10);
+            assert_eq!(read::unsigned(&mut &bytes[..]).expect("Should read number"), val);
+        }
+    }
+}
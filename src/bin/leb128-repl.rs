@@ -7,7 +7,7 @@ fn display(bytes: &[u8]) -> String {
     let mut s = vec![];
 
     // Base 10.
-    write!(&mut s, "{:?}\n", bytes).unwrap();
+    writeln!(&mut s, "{:?}", bytes).unwrap();
 
     // Hex.
     write!(&mut s, "[").unwrap();
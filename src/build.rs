@@ -0,0 +1,69 @@
+//! Helpers for generating static LEB128 data from a build script.
+//!
+//! Add `leb128` to `[build-dependencies]`, then from `build.rs`:
+//!
+//! ```no_run
+//! use std::env;
+//! use std::fs::File;
+//! use std::path::Path;
+//!
+//! let out = Path::new(&env::var("OUT_DIR").unwrap()).join("table.rs");
+//! let mut file = File::create(out).unwrap();
+//! leb128::build::write_rust_source(&mut file, "TABLE", &[1, 300, 70000]).unwrap();
+//! ```
+//!
+//! and include the generated file with
+//! `include!(concat!(env!("OUT_DIR"), "/table.rs"));` to get a `TABLE` blob
+//! of concatenated LEB128 numbers and a `TABLE_OFFSETS` slice holding the
+//! offset of each number within it.
+
+use std::io;
+use write;
+
+/// Encode each of the given numbers as unsigned LEB128 and return them
+/// concatenated.
+pub fn encode_all(vals: &[u64]) -> Vec<u8> {
+    let mut buf = vec![];
+    for &val in vals {
+        write::unsigned(&mut buf, val).expect("writing to a Vec cannot fail");
+    }
+    buf
+}
+
+/// Write Rust source declaring `pub static <name>: &[u8]`, holding the given
+/// numbers encoded as concatenated unsigned LEB128, and `pub static
+/// <name>_OFFSETS: &[usize]`, holding the offset of each number within it.
+pub fn write_rust_source<W>(w: &mut W, name: &str, vals: &[u64]) -> Result<(), io::Error>
+    where W: ?Sized + io::Write
+{
+    let mut blob = vec![];
+    let mut offsets = vec![];
+    for &val in vals {
+        offsets.push(blob.len());
+        write::unsigned(&mut blob, val)?;
+    }
+
+    writeln!(w, "pub static {}: &[u8] = &{:?};", name, blob)?;
+    writeln!(w, "pub static {}_OFFSETS: &[usize] = &{:?};", name, offsets)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_all() {
+        assert_eq!(encode_all(&[]), Vec::<u8>::new());
+        assert_eq!(encode_all(&[2, 128, 0]), [2, 0x80, 1, 0]);
+    }
+
+    #[test]
+    fn test_write_rust_source() {
+        let mut buf = vec![];
+        write_rust_source(&mut buf, "TABLE", &[2, 128, 0]).expect("Should write source");
+        assert_eq!(String::from_utf8(buf).unwrap(),
+                   "pub static TABLE: &[u8] = &[2, 128, 1, 0];\n\
+                    pub static TABLE_OFFSETS: &[usize] = &[0, 1, 3];\n");
+    }
+}
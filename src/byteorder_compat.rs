@@ -0,0 +1,37 @@
+//! Integration with the `byteorder` crate.
+//!
+//! A single glob import brings both the fixed-width readers from `byteorder`
+//! and the LEB128 readers from this crate into scope:
+//!
+//! ```
+//! use leb128::byteorder_compat::*;
+//!
+//! let buf = [0x34, 0x12, 0xe5, 0x8e, 0x26];
+//! let mut readable = &buf[..];
+//! assert_eq!(readable.read_u16::<LittleEndian>().unwrap(), 0x1234);
+//! assert_eq!(readable.read_uleb128().unwrap(), 624485);
+//! ```
+
+pub use byteorder::{BigEndian, ByteOrder, LittleEndian, NativeEndian, NetworkEndian,
+                    ReadBytesExt};
+
+use read;
+use std::io;
+
+/// Extends `byteorder::ReadBytesExt` with methods for reading LEB128 encoded
+/// numbers.
+pub trait ReadLeb128Ext: ReadBytesExt {
+    /// Read an unsigned LEB128 number. See `leb128::read::unsigned`.
+    #[inline]
+    fn read_uleb128(&mut self) -> Result<u64, read::Error> {
+        read::unsigned(self)
+    }
+
+    /// Read a signed LEB128 number. See `leb128::read::signed`.
+    #[inline]
+    fn read_sleb128(&mut self) -> Result<i64, read::Error> {
+        read::signed(self)
+    }
+}
+
+impl<R: io::Read + ?Sized> ReadLeb128Ext for R {}
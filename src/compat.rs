@@ -0,0 +1,156 @@
+//! Frozen shims with the original signatures of the core readers and writers.
+//!
+//! The functions in this module keep the exact signatures that
+//! `leb128::read::unsigned`, `leb128::read::signed`, `leb128::write::unsigned`
+//! and `leb128::write::signed` had in version 0.2 of this crate, and will
+//! keep them across future releases even if the top-level functions change,
+//! for example to return richer error types. Code that must not be affected
+//! by such changes can depend on these instead.
+//!
+//! ```
+//! use leb128::compat;
+//!
+//! let mut buf = vec![];
+//! compat::write::unsigned(&mut buf, 98765).unwrap();
+//! assert_eq!(compat::read::unsigned(&mut &buf[..]).unwrap(), 98765);
+//! ```
+
+/// The original reading functions.
+pub mod read {
+    use std::fmt;
+    use std::io;
+
+    /// The original error type, with only the variants of version 0.2.
+    #[derive(Debug)]
+    pub enum Error {
+        /// There was an underlying IO error.
+        IoError(io::Error),
+        /// The number being read is larger than can be represented.
+        Overflow,
+    }
+
+    impl From<io::Error> for Error {
+        fn from(e: io::Error) -> Self {
+            Error::IoError(e)
+        }
+    }
+
+    /// Errors that version 0.2 could not produce are folded into an
+    /// `InvalidData` IO error carrying their description.
+    impl From<::read::Error> for Error {
+        fn from(e: ::read::Error) -> Self {
+            match e {
+                ::read::Error::IoError(e) => Error::IoError(e),
+                ::read::Error::Overflow => Error::Overflow,
+                e => {
+                    let message = ::read::Message(&e).to_string();
+                    Error::IoError(io::Error::new(io::ErrorKind::InvalidData, message))
+                }
+            }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+            match *self {
+                Error::IoError(ref e) => write!(f, "leb128::read::Error: {}", e),
+                Error::Overflow => {
+                    write!(f,
+                           "leb128::read::Error: The number being read is larger than can be \
+                            represented")
+                }
+            }
+        }
+    }
+
+    impl ::std::error::Error for Error {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match *self {
+                Error::IoError(ref e) => Some(e),
+                Error::Overflow => None,
+            }
+        }
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it or an error if reading failed.
+    #[inline]
+    pub fn unsigned<R>(r: &mut R) -> Result<u64, Error>
+        where R: io::Read
+    {
+        Ok(::read::unsigned(r)?)
+    }
+
+    /// Read a signed LEB128 number from the given `std::io::Read`able and
+    /// return it or an error if reading failed.
+    #[inline]
+    pub fn signed<R>(r: &mut R) -> Result<i64, Error>
+        where R: io::Read
+    {
+        Ok(::read::signed(r)?)
+    }
+}
+
+/// The original writing functions.
+pub mod write {
+    use std::io;
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able. Returns the number of bytes written to `w`, or an
+    /// error if writing failed.
+    #[inline]
+    pub fn unsigned<W>(w: &mut W, val: u64) -> Result<usize, io::Error>
+        where W: io::Write
+    {
+        ::write::unsigned(w, val)
+    }
+
+    /// Write the given signed number using the LEB128 encoding to the given
+    /// `std::io::Write`able. Returns the number of bytes written to `w`, or an
+    /// error if writing failed.
+    #[inline]
+    pub fn signed<W>(w: &mut W, val: i64) -> Result<usize, io::Error>
+        where W: io::Write
+    {
+        ::write::signed(w, val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_read_errors() {
+        match read::unsigned(&mut &[0x80][..]) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        let err = read::unsigned(&mut &buf[..]).unwrap_err();
+        match err {
+            read::Error::Overflow => {}
+            ref otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(err.to_string(),
+                   "leb128::read::Error: The number being read is larger than can be \
+                    represented");
+    }
+
+    #[test]
+    fn test_newer_errors_are_folded() {
+        let err = read::Error::from(::read::Error::NonCanonical);
+        assert_eq!(err.to_string(),
+                   "leb128::read::Error: The number being read is encoded with redundant bytes");
+        match err {
+            read::Error::IoError(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::Error::from(::read::Error::Overflow) {
+            read::Error::Overflow => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Sequential reading of LEB128 numbers from a byte slice.
+
+use read;
+
+/// A position in a byte slice from which LEB128 numbers are read in turn.
+///
+/// Reading a number advances the cursor past it. If a number cannot be read,
+/// the cursor is left at its first byte, so `position` tells where the bad
+/// number starts.
+///
+/// ```
+/// let buf = [0x80, 0x01, 0x7f, 0x80];
+/// let mut cursor = leb128::SliceCursor::new(&buf);
+/// assert_eq!(cursor.read_uleb().unwrap(), 128);
+/// assert_eq!(cursor.read_sleb().unwrap(), -1);
+/// assert!(cursor.read_uleb().is_err());
+/// assert_eq!(cursor.position(), 3);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SliceCursor<'a> {
+    buf: &'a [u8],
+    position: usize,
+}
+
+impl<'a> SliceCursor<'a> {
+    /// Create a cursor at the start of `buf`.
+    pub fn new(buf: &'a [u8]) -> SliceCursor<'a> {
+        SliceCursor { buf, position: 0 }
+    }
+
+    /// Return the offset of the next byte to be read from the start of the
+    /// slice.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Return the bytes that have not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        &self.buf[self.position..]
+    }
+
+    /// Return `true` if every byte has been read.
+    pub fn is_empty(&self) -> bool {
+        self.position == self.buf.len()
+    }
+
+    /// Read an unsigned LEB128 number and advance past it, or return an error
+    /// without advancing if it is truncated or overflows.
+    pub fn read_uleb(&mut self) -> Result<u64, read::Error> {
+        self.read_with(read::unsigned)
+    }
+
+    /// Read a signed LEB128 number and advance past it, or return an error
+    /// without advancing if it is truncated or overflows.
+    pub fn read_sleb(&mut self) -> Result<i64, read::Error> {
+        self.read_with(read::signed)
+    }
+
+    fn read_with<T, F>(&mut self, read: F) -> Result<T, read::Error>
+        where F: FnOnce(&mut &'a [u8]) -> Result<T, read::Error>
+    {
+        let mut rest = self.remaining();
+        let val = read(&mut rest)?;
+        self.position = self.buf.len() - rest.len();
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use write;
+
+    #[test]
+    fn test_sequential() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+        write::signed(&mut buf, -65).expect("Should write number");
+        write::unsigned(&mut buf, u64::MAX).expect("Should write number");
+        write::signed(&mut buf, i64::MIN).expect("Should write number");
+
+        let mut cursor = SliceCursor::new(&buf);
+        assert_eq!(cursor.read_uleb().expect("Should read number"), 12857);
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(cursor.read_sleb().expect("Should read number"), -65);
+        assert_eq!(cursor.position(), 4);
+        assert_eq!(cursor.read_uleb().expect("Should read number"), u64::MAX);
+        assert_eq!(cursor.read_sleb().expect("Should read number"), i64::MIN);
+        assert!(cursor.is_empty());
+        assert_eq!(cursor.remaining(), []);
+
+        assert_eq!(cursor.read_uleb().unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(cursor.position(), buf.len());
+    }
+
+    #[test]
+    fn test_error_keeps_position() {
+        let buf = [0x02, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        let mut cursor = SliceCursor::new(&buf);
+        assert_eq!(cursor.read_uleb().expect("Should read number"), 2);
+        assert_eq!(cursor.read_uleb().unwrap_err().kind(), read::ErrorKind::Overflow);
+        assert_eq!(cursor.position(), 1);
+        assert_eq!(cursor.remaining(), &buf[1..]);
+
+        let mut cursor = SliceCursor::new(&buf[..3]);
+        assert_eq!(cursor.read_uleb().expect("Should read number"), 2);
+        assert_eq!(cursor.read_sleb().unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(cursor.position(), 1);
+    }
+}
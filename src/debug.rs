@@ -0,0 +1,65 @@
+//! Textual helpers for test fixtures and debugging.
+
+use read;
+use std::io;
+
+/// Parse the given string of whitespace-separated hex bytes, each optionally
+/// prefixed with `0x`, as a LEB128 stream and decode the first unsigned
+/// number from it. Returns an error if a byte is not valid hex or the number
+/// cannot be read.
+///
+/// ```
+/// assert_eq!(leb128::debug::decode_str("e5 8e 26").unwrap(), 624485);
+/// assert_eq!(leb128::debug::decode_str("0x80 0x01").unwrap(), 128);
+/// ```
+pub fn decode_str(s: &str) -> Result<u64, read::Error> {
+    let mut bytes = vec![];
+    for token in s.split_whitespace() {
+        let digits = if token.starts_with("0x") || token.starts_with("0X") {
+            &token[2..]
+        } else {
+            token
+        };
+        // `from_str_radix` would also accept a leading sign.
+        let valid = !digits.is_empty() && digits.len() <= 2 &&
+                    digits.bytes().all(|b| b.is_ascii_hexdigit());
+        if !valid {
+            return Err(read::Error::IoError(io::Error::new(io::ErrorKind::InvalidInput,
+                                                           format!("invalid hex byte {:?}",
+                                                                   token))));
+        }
+        bytes.push(u8::from_str_radix(digits, 16).expect("digits were checked"));
+    }
+    read::unsigned(&mut &bytes[..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_str() {
+        assert_eq!(decode_str("02").expect("Should decode"), 2);
+        assert_eq!(decode_str("  b9\t64\n").expect("Should decode"), 12857);
+        assert_eq!(decode_str("0x82 0X01 ff").expect("Should decode"), 130);
+        assert_eq!(decode_str("ff ff ff ff ff ff ff ff ff 01").expect("Should decode"),
+                   u64::MAX);
+    }
+
+    #[test]
+    fn test_decode_str_invalid() {
+        assert_eq!(decode_str("").unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(decode_str("80").unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(decode_str("ff ff ff ff ff ff ff ff ff 02").unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+
+        for s in &["zz", "100", "0x", "80 g1", "+5", "0x+f"] {
+            match decode_str(s) {
+                Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidInput),
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+    }
+}
@@ -0,0 +1,110 @@
+//! DWARF primitives that commonly appear next to LEB128 numbers.
+//!
+//! These are not LEB128 encodings, but fixed-width companions of them that
+//! every DWARF parser needs. All multi-byte values are little-endian.
+
+use read;
+use std::io;
+
+/// The initial length value that signals the 64-bit DWARF format.
+const DWARF_64_ESCAPE: u32 = 0xffff_ffff;
+
+/// The lowest initial length value that is reserved by the DWARF standard.
+const RESERVED_START: u32 = 0xffff_fff0;
+
+/// Read a DWARF "initial length" from the given `std::io::Read`able.
+///
+/// In the 32-bit DWARF format the length is a 4 byte number. In the 64-bit
+/// format it is the 4 byte escape `0xffffffff` followed by an 8 byte number.
+/// Returns the length and whether the 64-bit format was used, or an error if
+/// reading failed or the length is one of the reserved values
+/// `0xfffffff0..0xffffffff`.
+pub fn read_initial_length<R>(r: &mut R) -> Result<(u64, bool), read::Error>
+    where R: ?Sized + io::Read
+{
+    let mut buf = [0; 4];
+    r.read_exact(&mut buf)?;
+    let len = u32::from_le_bytes(buf);
+
+    if len == DWARF_64_ESCAPE {
+        let mut buf = [0; 8];
+        r.read_exact(&mut buf)?;
+        return Ok((u64::from_le_bytes(buf), true));
+    }
+
+    if len >= RESERVED_START {
+        return Err(read::Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                       "reserved DWARF initial length")));
+    }
+
+    Ok((len as u64, false))
+}
+
+/// Write the given length as a DWARF "initial length" to the given
+/// `std::io::Write`able, using the 64-bit format if `dwarf64` is set and the
+/// 32-bit format otherwise. Returns the number of bytes written to `w`, or an
+/// error if the length does not fit in the 32-bit format or writing failed.
+pub fn write_initial_length<W>(w: &mut W, len: u64, dwarf64: bool) -> Result<usize, io::Error>
+    where W: ?Sized + io::Write
+{
+    if dwarf64 {
+        w.write_all(&DWARF_64_ESCAPE.to_le_bytes())?;
+        w.write_all(&len.to_le_bytes())?;
+        return Ok(12);
+    }
+
+    if len >= RESERVED_START as u64 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                  "length does not fit in the 32-bit DWARF format"));
+    }
+
+    w.write_all(&(len as u32).to_le_bytes())?;
+    Ok(4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_initial_length_32() {
+        let mut buf = vec![];
+        assert_eq!(write_initial_length(&mut buf, 0x1234, false).expect("Should write length"),
+                   4);
+        assert_eq!(buf, [0x34, 0x12, 0, 0]);
+        assert_eq!(read_initial_length(&mut &buf[..]).expect("Should read length"),
+                   (0x1234, false));
+    }
+
+    #[test]
+    fn test_initial_length_64() {
+        let mut buf = vec![];
+        assert_eq!(write_initial_length(&mut buf, 0x1_0000_0000, true)
+                       .expect("Should write length"),
+                   12);
+        assert_eq!(buf, [0xff, 0xff, 0xff, 0xff, 0, 0, 0, 0, 1, 0, 0, 0]);
+        assert_eq!(read_initial_length(&mut &buf[..]).expect("Should read length"),
+                   (0x1_0000_0000, true));
+    }
+
+    #[test]
+    fn test_initial_length_reserved() {
+        let buf = [0xf0u8, 0xff, 0xff, 0xff];
+        match read_initial_length(&mut &buf[..]) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        match write_initial_length(&mut vec![], 0xffff_fff0, false) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidInput),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_initial_length_not_enough_data() {
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0];
+        assert_eq!(read_initial_length(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+}
@@ -0,0 +1,83 @@
+//! In-place edits of LEB128 encoded numbers.
+
+use read;
+use std::io;
+use write;
+
+/// Add `by` to the unsigned LEB128 number that occupies all of `buf`, and
+/// re-encode the sum in place, padded to the same number of bytes.
+///
+/// Returns an error if `buf` does not hold exactly one number, or an
+/// `Error::Overflow` if the sum does not fit in the field. The field is left
+/// unchanged on error.
+///
+/// ```
+/// let mut counter = [0x80, 0x80, 0x00];
+/// leb128::edit::increment_fixed_width(&mut counter, 200).unwrap();
+/// assert_eq!(counter, [0xc8, 0x81, 0x00]);
+/// ```
+pub fn increment_fixed_width(buf: &mut [u8], by: u64) -> Result<(), read::Error> {
+    let len = buf.len();
+    let val = {
+        let mut readable = &buf[..];
+        let val = read::unsigned(&mut readable)?;
+        if !readable.is_empty() {
+            return Err(read::Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                           "number is shorter than its field")));
+        }
+        val
+    };
+
+    let sum = val.checked_add(by).ok_or(read::Error::Overflow)?;
+    if write::unsigned_len(sum) > len {
+        return Err(read::Error::Overflow);
+    }
+
+    let written = write::unsigned_min_bytes(&mut &mut buf[..], sum, len)?;
+    debug_assert_eq!(written, len);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_increment_fixed_width() {
+        let mut buf = [0u8];
+        increment_fixed_width(&mut buf, 127).expect("Should increment");
+        assert_eq!(buf, [0x7f]);
+
+        let mut buf = [0x80u8, 0x80, 0x80, 0x00];
+        for _ in 0..1000 {
+            increment_fixed_width(&mut buf, 3).expect("Should increment");
+        }
+        assert_eq!(read::unsigned(&mut &buf[..]).expect("Should read number"), 3000);
+        assert_eq!(buf.len(), 4);
+    }
+
+    #[test]
+    fn test_increment_fixed_width_overflow() {
+        let mut buf = [0xffu8, 0x7f];
+        assert_eq!(increment_fixed_width(&mut buf, 1).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(buf, [0xff, 0x7f]);
+
+        let mut buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert_eq!(increment_fixed_width(&mut buf, 1).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_increment_fixed_width_invalid_field() {
+        let mut buf = [0u8, 0];
+        match increment_fixed_width(&mut buf, 1) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        let mut buf = [0x80u8];
+        assert_eq!(increment_fixed_width(&mut buf, 1).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+}
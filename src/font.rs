@@ -0,0 +1,126 @@
+//! The `UIntBase128` variable length encoding used by the WOFF2 font format.
+//!
+//! This is not LEB128. Each byte still carries seven bits of the number and a
+//! continuation bit, but the bytes are big-endian, most significant group
+//! first. The number must fit in a `u32`, so it takes at most five bytes, and
+//! it must not have leading zero groups, so a first byte of `0x80` is invalid.
+//!
+//! ```
+//! let mut buf = vec![];
+//! leb128::font::write_uint(&mut buf, 16384).unwrap();
+//! assert_eq!(buf, [0x81, 0x80, 0x00]);
+//! assert_eq!(leb128::font::read_uint(&mut &buf[..]).unwrap(), 16384);
+//! ```
+
+use read;
+use std::io;
+
+/// The most bytes a `UIntBase128` number may take.
+const MAX_LEN: usize = 5;
+
+/// Read a `UIntBase128` number from the given `std::io::Read`able and return
+/// it, or an error if reading failed.
+///
+/// A number with a leading zero group is an `InvalidData` IO error, and one
+/// that does not fit in a `u32` or takes more than five bytes is an
+/// `Error::Overflow`.
+pub fn read_uint<R>(r: &mut R) -> Result<u32, read::Error>
+    where R: ?Sized + io::Read
+{
+    let mut result: u32 = 0;
+    for i in 0..MAX_LEN {
+        let mut buf = [0];
+        r.read_exact(&mut buf)?;
+        let byte = buf[0];
+
+        if i == 0 && byte == 0x80 {
+            return Err(read::Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                           "leading zero group")));
+        }
+        // Shifting in another seven bits would lose the top ones.
+        if result & 0xfe00_0000 != 0 {
+            return Err(read::Error::Overflow);
+        }
+
+        result = (result << 7) | (byte & 0x7f) as u32;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err(read::Error::Overflow)
+}
+
+/// Write the given number as a `UIntBase128` to the given
+/// `std::io::Write`able. Returns the number of bytes written to `w`, or an
+/// error if writing failed.
+pub fn write_uint<W>(w: &mut W, val: u32) -> Result<usize, io::Error>
+    where W: ?Sized + io::Write
+{
+    let bits = 32 - val.leading_zeros();
+    let len = if bits == 0 { 1 } else { ::max_bytes(bits) };
+
+    let mut buf = [0; MAX_LEN];
+    for (i, byte) in buf[..len].iter_mut().enumerate() {
+        let shift = (len - 1 - i) * 7;
+        *byte = (val >> shift) as u8 & 0x7f;
+        if i + 1 < len {
+            *byte |= 0x80;
+        }
+    }
+    w.write_all(&buf[..len])?;
+    Ok(len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Worked from the `ReadUIntBase128` definition in section 4.1 of the
+    // WOFF2 specification.
+    const VECTORS: [(u32, &[u8]); 8] = [(0, &[0x00]),
+                                        (63, &[0x3f]),
+                                        (127, &[0x7f]),
+                                        (128, &[0x81, 0x00]),
+                                        (16383, &[0xff, 0x7f]),
+                                        (16384, &[0x81, 0x80, 0x00]),
+                                        (0x0fff_ffff, &[0xff, 0xff, 0xff, 0x7f]),
+                                        (u32::MAX, &[0x8f, 0xff, 0xff, 0xff, 0x7f])];
+
+    #[test]
+    fn test_vectors() {
+        for &(val, bytes) in &VECTORS {
+            let mut buf = vec![];
+            assert_eq!(write_uint(&mut buf, val).expect("Should write number"), bytes.len());
+            assert_eq!(buf, bytes);
+            assert_eq!(read_uint(&mut &bytes[..]).expect("Should read number"), val);
+        }
+    }
+
+    #[test]
+    fn test_not_leb128() {
+        // 128 as unsigned LEB128 is a different byte sequence.
+        let mut leb = vec![];
+        ::write::unsigned(&mut leb, 128).expect("Should write number");
+        assert_eq!(leb, [0x80, 0x01]);
+        match read_uint(&mut &leb[..]) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        // Too large for a `u32`.
+        match read_uint(&mut &[0x90, 0x80, 0x80, 0x80, 0x00][..]) {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        // More than five bytes.
+        match read_uint(&mut &[0x81, 0x80, 0x80, 0x80, 0x80, 0x00][..]) {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(read_uint(&mut &[0x81][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+}
@@ -0,0 +1,105 @@
+//! The group varint block format, which encodes four `u32`s at a time.
+//!
+//! This is not LEB128. A block starts with a descriptor byte holding, for
+//! each of the four numbers from the least significant bits up, two bits of
+//! its length in bytes minus one. The numbers follow in little-endian order,
+//! each in as few bytes as it needs. Knowing all four lengths up front lets a
+//! decoder avoid testing a continuation bit per byte, which makes decoding
+//! faster than LEB128 in read-heavy workloads.
+
+use read;
+use std::io;
+
+/// Encode the given four numbers as a group varint block.
+///
+/// ```
+/// let block = leb128::group::encode4([1, 256, 0, 65536]);
+/// assert_eq!(block, [0b10_00_01_00, 1, 0, 1, 0, 0, 0, 1]);
+/// ```
+pub fn encode4(vals: [u32; 4]) -> Vec<u8> {
+    let mut buf = vec![0];
+    for (i, &val) in vals.iter().enumerate() {
+        let len = (4 - val.leading_zeros() as usize / 8).max(1);
+        buf[0] |= ((len - 1) as u8) << (i * 2);
+        buf.extend_from_slice(&val.to_le_bytes()[..len]);
+    }
+    buf
+}
+
+/// Decode a group varint block from the start of `buf`, and return its four
+/// numbers along with the rest of `buf`, or an `UnexpectedEof` IO error if the
+/// block is truncated.
+pub fn decode4(buf: &[u8]) -> Result<([u32; 4], &[u8]), read::Error> {
+    let (&descriptor, mut rest) = buf.split_first().ok_or_else(truncated)?;
+
+    let mut vals = [0; 4];
+    for (i, val) in vals.iter_mut().enumerate() {
+        let len = ((descriptor >> (i * 2)) & 0b11) as usize + 1;
+        if rest.len() < len {
+            return Err(truncated());
+        }
+        let mut bytes = [0; 4];
+        bytes[..len].copy_from_slice(&rest[..len]);
+        *val = u32::from_le_bytes(bytes);
+        rest = &rest[len..];
+    }
+
+    Ok((vals, rest))
+}
+
+fn truncated() -> read::Error {
+    read::Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                        "truncated group varint block"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use write;
+
+    #[test]
+    fn test_round_trip() {
+        let blocks = [[0, 0, 0, 0],
+                      [1, 127, 128, 255],
+                      [256, 65535, 65536, 1 << 24],
+                      [u32::MAX, 0, u32::MAX, 12857]];
+
+        let mut buf = vec![];
+        let mut leb = vec![];
+        for block in &blocks {
+            buf.extend(encode4(*block));
+            for &val in block {
+                write::unsigned(&mut leb, val as u64).expect("Should write number");
+            }
+        }
+
+        let mut rest = &buf[..];
+        let mut leb = &leb[..];
+        for block in &blocks {
+            let (vals, tail) = decode4(rest).expect("Should decode block");
+            assert_eq!(vals, *block);
+            for &val in &vals {
+                assert_eq!(read::unsigned(&mut leb).expect("Should read number"), val as u64);
+            }
+            rest = tail;
+        }
+        assert!(rest.is_empty());
+        assert!(leb.is_empty());
+    }
+
+    #[test]
+    fn test_lengths() {
+        assert_eq!(encode4([0, 0, 0, 0]).len(), 5);
+        assert_eq!(encode4([u32::MAX; 4]).len(), 17);
+        assert_eq!(encode4([u32::MAX; 4])[0], 0xff);
+    }
+
+    #[test]
+    fn test_truncated() {
+        let block = encode4([1, 256, 65536, u32::MAX]);
+        for len in 0..block.len() {
+            assert_eq!(decode4(&block[..len]).unwrap_err().kind(),
+                       read::ErrorKind::UnexpectedEndOfData);
+        }
+    }
+}
@@ -42,11 +42,72 @@
 
 #![deny(missing_docs)]
 
+#[cfg(feature = "arbitrary")]
+extern crate arbitrary as arbitrary_crate;
+#[cfg(feature = "byteorder-compat")]
+extern crate byteorder;
+
+#[cfg(feature = "derive")]
+extern crate leb128_derive;
+#[cfg(feature = "bigint")]
+extern crate num_bigint;
+
+pub mod analyze;
+#[cfg(feature = "arbitrary")]
+pub mod arbitrary;
+pub mod build;
+#[cfg(feature = "byteorder-compat")]
+pub mod byteorder_compat;
+pub mod compat;
+mod cursor;
+pub mod debug;
+pub mod dwarf;
+pub mod edit;
+pub mod font;
+pub mod group;
+pub mod msgpack_hybrid;
+pub mod reference;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+pub mod transform;
+
+#[cfg(feature = "derive")]
+pub use leb128_derive::Leb128;
+pub use cursor::SliceCursor;
+pub use read::LazyValue;
+
 #[doc(hidden)]
 pub const CONTINUATION_BIT: u8 = 1 << 7;
 #[doc(hidden)]
 pub const SIGN_BIT: u8 = 1 << 6;
 
+/// Return the maximum number of bytes needed to LEB128 encode a number that
+/// is `bits` bits wide.
+///
+/// ```
+/// const MAX_U40_LEN: usize = leb128::max_bytes(40);
+/// let buf = [0u8; MAX_U40_LEN];
+/// assert_eq!(buf.len(), 6);
+/// ```
+pub const fn max_bytes(bits: u32) -> usize {
+    bits.div_ceil(7) as usize
+}
+
+/// Return the largest unsigned number that can be LEB128 encoded in `bytes`
+/// bytes, saturating at `u128::MAX`.
+///
+/// ```
+/// const MAX_U14: u128 = leb128::max_value_in_bytes(2);
+/// assert_eq!(MAX_U14, 16383);
+/// ```
+pub const fn max_value_in_bytes(bytes: usize) -> u128 {
+    if bytes >= 19 {
+        u128::MAX
+    } else {
+        (1 << (bytes * 7)) - 1
+    }
+}
+
 #[doc(hidden)]
 #[inline]
 pub fn low_bits_of_byte(byte: u8) -> u8 {
@@ -56,16 +117,32 @@ pub fn low_bits_of_byte(byte: u8) -> u8 {
 #[doc(hidden)]
 #[inline]
 pub fn low_bits_of_u64(val: u64) -> u8 {
-    let byte = val & (std::u8::MAX as u64);
+    let byte = val & (u8::MAX as u64);
     low_bits_of_byte(byte as u8)
 }
 
 /// A module for reading signed and unsigned integers that have been LEB128
 /// encoded.
+///
+/// Bytes are read with `std::io::Read::read_exact`, so a read that fails with
+/// `std::io::ErrorKind::Interrupted` is retried, while a read that returns
+/// `Ok(0)` is taken as the end of input, as the `std::io::Read` contract
+/// specifies. Ending in the middle of a number is then an `UnexpectedEof` IO
+/// error, even if the reader would later produce more data.
 pub mod read {
-    use super::{CONTINUATION_BIT, SIGN_BIT, low_bits_of_byte};
+    use super::{CONTINUATION_BIT, SIGN_BIT, low_bits_of_byte, max_bytes};
+    use super::write::{CountingWriter, signed_len, unsigned_len};
     use std::fmt;
+    use std::convert::TryFrom;
+    use std::hash::Hasher;
     use std::io;
+    use std::iter;
+    use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+    use std::num::{NonZeroU32, NonZeroU64};
+    use std::ops::Range;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    #[cfg(feature = "bigint")]
+    use num_bigint::BigUint;
 
     /// An enumeration of the possible errors that can occur when reading a
     /// number encoded with LEB128.
@@ -73,8 +150,82 @@ pub mod read {
     pub enum Error {
         /// There was an underlying IO error.
         IoError(io::Error),
+        /// The number being read is larger than can be represented. Use
+        /// `unsigned_detailed` or `signed_detailed` to find out which byte
+        /// overflowed.
+        Overflow,
+        /// The number read is not the one that was expected.
+        Mismatch {
+            /// The number that was expected.
+            expected: u64,
+            /// The number that was read instead.
+            found: u64,
+        },
+        /// The number read is not a Unicode scalar value.
+        InvalidChar(u64),
+        /// The number read is zero, but was required to be nonzero.
+        UnexpectedZero,
+        /// The number was encoded with more bytes than necessary, which is
+        /// rejected when reading strictly.
+        NonCanonical,
+        /// A configured limit on the input was exceeded.
+        LimitExceeded,
+        /// The number read is not a known discriminant.
+        UnknownDiscriminant(u64),
+        /// The checksum following a number does not match its encoding.
+        ChecksumMismatch {
+            /// The checksum computed from the encoding.
+            expected: u8,
+            /// The checksum that was read instead.
+            found: u8,
+        },
+    }
+
+    impl Error {
+        /// Return the kind of this error, which unlike the error itself can be
+        /// compared for equality.
+        pub fn kind(&self) -> ErrorKind {
+            match *self {
+                Error::IoError(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                    ErrorKind::UnexpectedEndOfData
+                }
+                Error::IoError(_) => ErrorKind::Io,
+                Error::Overflow => ErrorKind::Overflow,
+                Error::Mismatch { .. } => ErrorKind::Mismatch,
+                Error::InvalidChar(_) => ErrorKind::InvalidChar,
+                Error::UnexpectedZero => ErrorKind::UnexpectedZero,
+                Error::NonCanonical => ErrorKind::NonCanonical,
+                Error::LimitExceeded => ErrorKind::LimitExceeded,
+                Error::UnknownDiscriminant(_) => ErrorKind::UnknownDiscriminant,
+                Error::ChecksumMismatch { .. } => ErrorKind::ChecksumMismatch,
+            }
+        }
+    }
+
+    /// A comparable summary of a read `Error`, as returned by `Error::kind`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum ErrorKind {
+        /// There was an underlying IO error other than reaching the end of
+        /// input.
+        Io,
+        /// The input ended in the middle of a number.
+        UnexpectedEndOfData,
         /// The number being read is larger than can be represented.
         Overflow,
+        /// The number read is not the one that was expected.
+        Mismatch,
+        /// The number read is not a Unicode scalar value.
+        InvalidChar,
+        /// The number read is zero, but was required to be nonzero.
+        UnexpectedZero,
+        /// The number was encoded with more bytes than necessary.
+        NonCanonical,
+        /// A configured limit on the input was exceeded.
+        LimitExceeded,
+        /// The number read is not a known discriminant.
+        UnknownDiscriminant,
+        /// The checksum following a number does not match its encoding.
+        ChecksumMismatch,
     }
 
     impl From<io::Error> for Error {
@@ -85,72 +236,295 @@ pub mod read {
 
     impl fmt::Display for Error {
         fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-            write!(f,
-                   "leb128::read::Error: {}",
-                   ::std::error::Error::description(self))
+            write!(f, "leb128::read::Error: {}", Message(self))
+        }
+    }
+
+    /// The description of an `Error`, without the prefix naming its type.
+    pub(crate) struct Message<'a>(pub(crate) &'a Error);
+
+    impl<'a> fmt::Display for Message<'a> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+            match *self.0 {
+                Error::IoError(ref e) => e.fmt(f),
+                Error::Overflow => {
+                    write!(f, "The number being read is larger than can be represented")
+                }
+                Error::Mismatch { expected, found } => {
+                    write!(f, "Expected the number {} but found {}", expected, found)
+                }
+                Error::InvalidChar(val) => {
+                    write!(f, "The number {:#x} is not a Unicode scalar value", val)
+                }
+                Error::UnexpectedZero => write!(f, "The number being read is unexpectedly zero"),
+                Error::NonCanonical => {
+                    write!(f, "The number being read is encoded with redundant bytes")
+                }
+                Error::LimitExceeded => write!(f, "A configured limit on the input was exceeded"),
+                Error::UnknownDiscriminant(val) => {
+                    write!(f, "The number {} is not a known discriminant", val)
+                }
+                Error::ChecksumMismatch { expected, found } => {
+                    write!(f,
+                           "Expected the checksum {:#04x} but found {:#04x}",
+                           expected,
+                           found)
+                }
+            }
         }
     }
 
     impl ::std::error::Error for Error {
-        fn description(&self) -> &str {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match *self {
+                Error::IoError(ref e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    /// Details about a number that was too large to be represented, as
+    /// reported by `unsigned_detailed` and `signed_detailed`.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Overflow {
+        /// The number of bytes read, including the byte that overflowed.
+        pub bytes_read: usize,
+        /// The bits accumulated from the bytes preceding the one that
+        /// overflowed.
+        pub partial: u64,
+    }
+
+    impl Overflow {
+        /// Return the zero-based index of the byte that overflowed within the
+        /// encoding of the number.
+        pub fn at_byte(&self) -> usize {
+            self.bytes_read - 1
+        }
+    }
+
+    /// An enumeration of the possible errors that can occur when reading a
+    /// number with `unsigned_detailed` or `signed_detailed`.
+    #[derive(Debug)]
+    pub enum DetailedError {
+        /// There was an underlying IO error.
+        IoError(io::Error),
+        /// The input ended in the middle of a number.
+        UnexpectedEndOfData {
+            /// The number of bytes of the number that were consumed before
+            /// the input ended.
+            consumed: usize,
+        },
+        /// The number being read is larger than can be represented.
+        Overflow(Overflow),
+    }
+
+    impl DetailedError {
+        /// Return the number of bytes of the number that were consumed before
+        /// the error, if known. An underlying IO error other than reaching the
+        /// end of input may have happened at any point.
+        pub fn consumed(&self) -> Option<usize> {
+            match *self {
+                DetailedError::IoError(_) => None,
+                DetailedError::UnexpectedEndOfData { consumed } => Some(consumed),
+                DetailedError::Overflow(ref o) => Some(o.bytes_read),
+            }
+        }
+    }
+
+    impl From<io::Error> for DetailedError {
+        fn from(e: io::Error) -> Self {
+            DetailedError::IoError(e)
+        }
+    }
+
+    impl From<DetailedError> for Error {
+        fn from(e: DetailedError) -> Self {
+            match e {
+                DetailedError::IoError(e) => Error::IoError(e),
+                DetailedError::UnexpectedEndOfData { .. } => {
+                    Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                  "truncated LEB128 number"))
+                }
+                DetailedError::Overflow(_) => Error::Overflow,
+            }
+        }
+    }
+
+    impl fmt::Display for DetailedError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
             match *self {
-                Error::IoError(ref e) => e.description(),
-                Error::Overflow => "The number being read is larger than can be represented",
+                DetailedError::IoError(ref e) => e.fmt(f),
+                DetailedError::UnexpectedEndOfData { consumed } => {
+                    write!(f,
+                           "The input ended after {} bytes of the number being read",
+                           consumed)
+                }
+                DetailedError::Overflow(ref o) => {
+                    write!(f,
+                           "The number being read is larger than can be represented \
+                            (overflowed at byte index {} with partial value {:#x})",
+                           o.at_byte(),
+                           o.partial)
+                }
             }
         }
+    }
 
-        fn cause(&self) -> Option<&::std::error::Error> {
+    impl ::std::error::Error for DetailedError {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
             match *self {
-                Error::IoError(ref e) => Some(e),
-                Error::Overflow => None,
+                DetailedError::IoError(ref e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    /// Read the next byte of a number of which `consumed` bytes have already
+    /// been read.
+    #[inline]
+    fn read_byte<R>(r: &mut R, consumed: usize) -> Result<u8, DetailedError>
+        where R: ?Sized + io::Read
+    {
+        let mut buf = [0];
+        match r.read_exact(&mut buf) {
+            Ok(()) => Ok(buf[0]),
+            Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                Err(DetailedError::UnexpectedEndOfData { consumed })
             }
+            Err(e) => Err(DetailedError::IoError(e)),
         }
     }
 
     /// Read an unsigned LEB128 number from the given `std::io::Read`able and
     /// return it or an error if reading failed.
+    ///
+    /// The tenth byte of a number holds only bit 63, so it must be either
+    /// `0x00` or `0x01`. Any other tenth byte, including one with the
+    /// continuation bit set, is an `Error::Overflow`.
     pub fn unsigned<R>(r: &mut R) -> Result<u64, Error>
-        where R: io::Read
+        where R: ?Sized + io::Read
     {
-        let mut result = 0;
-        let mut shift = 0;
+        Ok(unsigned_detailed(r)?)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it or an error if reading failed.
+    ///
+    /// This is the same as `unsigned`, except that errors report how many
+    /// bytes of the number were consumed, so that a parser can resynchronize
+    /// after damaged input, and an overflow error also reports the value
+    /// accumulated before overflowing.
+    pub fn unsigned_detailed<R>(r: &mut R) -> Result<u64, DetailedError>
+        where R: ?Sized + io::Read
+    {
+        let byte = read_byte(r, 0)?;
+        if byte & CONTINUATION_BIT == 0 {
+            // Fast path for the common single byte case.
+            return Ok(byte as u64);
+        }
+
+        // Accumulate in a `u128`, which can hold all the bits of the ten bytes
+        // a `u64` may occupy, so that overflow is a plain comparison.
+        let mut result = low_bits_of_byte(byte) as u128;
+        let mut shift: u32 = 7;
+        let mut bytes_read = 1;
 
         loop {
-            let mut buf = [0];
-            try!(r.read_exact(&mut buf));
+            let byte = read_byte(r, bytes_read)?;
+            bytes_read += 1;
 
-            if shift == 63 && buf[0] != 0x00 && buf[0] != 0x01 {
-                return Err(Error::Overflow);
-            }
+            let more = byte & CONTINUATION_BIT != 0;
+            let next = result | (low_bits_of_byte(byte) as u128) << shift;
 
-            let low_bits = low_bits_of_byte(buf[0]) as u64;
-            result |= low_bits << shift;
+            // The number overflows if it exceeds `u64::MAX`, or if more bytes
+            // follow the one that reaches bit 63.
+            if next > u64::MAX as u128 || (more && shift >= 63) {
+                return Err(DetailedError::Overflow(Overflow {
+                    bytes_read,
+                    partial: result as u64,
+                }));
+            }
+            result = next;
 
-            if buf[0] & CONTINUATION_BIT == 0 {
-                return Ok(result);
+            if !more {
+                return Ok(result as u64);
             }
 
             shift += 7;
         }
     }
 
+    /// The value of every first byte that is a complete number by itself.
+    const SINGLE_BYTE_VALUES: [Option<u64>; 256] = {
+        let mut table = [None; 256];
+        let mut byte = 0;
+        while byte < CONTINUATION_BIT as usize {
+            table[byte] = Some(byte as u64);
+            byte += 1;
+        }
+        table
+    };
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it or an error if reading failed.
+    ///
+    /// This is the same as `unsigned`, except that numbers that fit in a
+    /// single byte are looked up in a precomputed table, which can be faster
+    /// for inputs dominated by small numbers.
+    pub fn unsigned_tabled<R>(r: &mut R) -> Result<u64, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut buf = [0];
+        r.read_exact(&mut buf)?;
+        match SINGLE_BYTE_VALUES[buf[0] as usize] {
+            Some(val) => Ok(val),
+            None => unsigned(&mut io::Read::chain(&buf[..], &mut *r)),
+        }
+    }
+
     /// Read a signed LEB128 number from the given `std::io::Read`able and
     /// return it or an error if reading failed.
+    ///
+    /// Only a byte without the continuation bit ends the number, and the sign
+    /// is extended from that byte alone. If the input ends before such a byte
+    /// is read, an `UnexpectedEof` IO error is returned rather than a sign
+    /// extended partial value.
     pub fn signed<R>(r: &mut R) -> Result<i64, Error>
-        where R: io::Read
+        where R: ?Sized + io::Read
     {
-        let mut result = 0;
-        let mut shift = 0;
+        Ok(signed_detailed(r)?)
+    }
+
+    /// Read a signed LEB128 number from the given `std::io::Read`able and
+    /// return it or an error if reading failed.
+    ///
+    /// This is the same as `signed`, except that errors report how many bytes
+    /// of the number were consumed, and an overflow error also reports the
+    /// bits accumulated before overflowing.
+    pub fn signed_detailed<R>(r: &mut R) -> Result<i64, DetailedError>
+        where R: ?Sized + io::Read
+    {
+        let mut byte = read_byte(r, 0)?;
+        if byte & CONTINUATION_BIT == 0 {
+            // Fast path for the common single byte case, sign extending from
+            // the sign bit.
+            return Ok(((byte << 1) as i8 >> 1) as i64);
+        }
+
+        let mut result = low_bits_of_byte(byte) as i64;
+        let mut shift = 7;
         let size = 64;
-        let mut byte;
+        let mut bytes_read = 1;
 
         loop {
-            let mut buf = [0];
-            try!(r.read_exact(&mut buf));
+            byte = read_byte(r, bytes_read)?;
+            bytes_read += 1;
 
-            byte = buf[0];
             if shift == 63 && byte != 0x00 && byte != 0x7f {
-                return Err(Error::Overflow);
+                return Err(DetailedError::Overflow(Overflow {
+                    bytes_read,
+                    partial: result as u64,
+                }));
             }
 
             let low_bits = low_bits_of_byte(byte) as i64;
@@ -169,173 +543,2877 @@ pub mod read {
 
         Ok(result)
     }
-}
 
-/// A module for writing integers encoded as LEB128.
-pub mod write {
-    use super::{CONTINUATION_BIT, low_bits_of_u64};
-    use std::io;
+    /// Read an unsigned LEB128 number from the given `std::io::Read` trait
+    /// object. Unlike `unsigned`, this is not generic, so it can be stored as
+    /// a `fn` pointer.
+    pub fn unsigned_dyn(r: &mut dyn io::Read) -> Result<u64, Error> {
+        unsigned(r)
+    }
 
-    /// Write the given unsigned number using the LEB128 encoding to the given
-    /// `std::io::Write`able. Returns the number of bytes written to `w`, or an
-    /// error if writing failed.
-    pub fn unsigned<W>(w: &mut W, mut val: u64) -> Result<usize, io::Error>
-        where W: io::Write
-    {
-        let mut bytes_written = 0;
-        loop {
-            let mut byte = low_bits_of_u64(val);
-            val >>= 7;
-            if val != 0 {
-                // More bytes to come, so set the continuation bit.
-                byte |= CONTINUATION_BIT;
-            }
+    /// Read a signed LEB128 number from the given `std::io::Read` trait
+    /// object. Unlike `signed`, this is not generic, so it can be stored as a
+    /// `fn` pointer.
+    pub fn signed_dyn(r: &mut dyn io::Read) -> Result<i64, Error> {
+        signed(r)
+    }
 
-            let buf = [byte];
-            try!(w.write_all(&buf));
-            bytes_written += 1;
+    /// A decoding policy, for readers that need to be stricter than the
+    /// `unsigned` and `signed` functions.
+    ///
+    /// The default policy matches those functions: redundant padding bytes
+    /// are accepted, the encoding may be as long as needed, and numbers may
+    /// use all 64 bits. Decoders of nested or counted sequences produce at
+    /// most `DEFAULT_MAX_ELEMENTS` elements by default.
+    ///
+    /// ```
+    /// use leb128::read::{Error, Options};
+    ///
+    /// let options = Options::new().strict(true).max_bytes(Some(3)).bits(16);
+    ///
+    /// assert_eq!(options.unsigned(&mut &[0xff, 0xff, 0x03][..]).unwrap(), 0xffff);
+    /// match options.unsigned(&mut &[0x80, 0x00][..]) {
+    ///     Err(Error::NonCanonical) => {}
+    ///     otherwise => panic!("Unexpected: {:?}", otherwise),
+    /// }
+    /// ```
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct Options {
+        strict: bool,
+        max_bytes: Option<usize>,
+        bits: u32,
+        max_elements: Option<usize>,
+    }
 
-            if val == 0 {
-                return Ok(bytes_written);
+    /// The default limit on the number of elements that the decoders of nested
+    /// or counted sequences produce, so that untrusted input cannot make them
+    /// allocate without bound.
+    pub const DEFAULT_MAX_ELEMENTS: usize = 1 << 24;
+
+    impl Default for Options {
+        fn default() -> Self {
+            Options {
+                strict: false,
+                max_bytes: None,
+                bits: 64,
+                max_elements: Some(DEFAULT_MAX_ELEMENTS),
             }
         }
     }
 
-    /// Write the given signed number using the LEB128 encoding to the given
-    /// `std::io::Write`able. Returns the number of bytes written to `w`, or an
-    /// error if writing failed.
-    pub fn signed<W>(w: &mut W, mut val: i64) -> Result<usize, io::Error>
-        where W: io::Write
-    {
-        let mut bytes_written = 0;
-        loop {
-            let mut byte = val as u8;
-            // Keep the sign bit for testing
-            val >>= 6;
-            let done = val == 0 || val == -1;
-            if done {
-                byte &= !CONTINUATION_BIT;
-            } else {
-                // Remove the sign bit
-                val >>= 1;
-                // More bytes to come, so set the continuation bit.
-                byte |= CONTINUATION_BIT;
-            }
+    impl Options {
+        /// Create the default, lenient policy.
+        pub fn new() -> Self {
+            Self::default()
+        }
 
-            let buf = [byte];
-            try!(w.write_all(&buf));
-            bytes_written += 1;
+        /// Whether to reject numbers encoded with redundant padding bytes with
+        /// an `Error::NonCanonical`.
+        pub fn strict(mut self, strict: bool) -> Self {
+            self.strict = strict;
+            self
+        }
 
-            if done {
-                return Ok(bytes_written);
-            }
+        /// The maximum number of bytes an encoding may occupy, beyond which an
+        /// `Error::LimitExceeded` is returned, or `None` for no limit.
+        pub fn max_bytes(mut self, max_bytes: Option<usize>) -> Self {
+            self.max_bytes = max_bytes;
+            self
         }
-    }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std;
-    use std::io;
+        /// The width in bits that numbers must fit in, beyond which an
+        /// `Error::Overflow` is returned. Signed numbers must fit in this width
+        /// as two's complement.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `bits` is not between 1 and 64.
+        pub fn bits(mut self, bits: u32) -> Self {
+            assert!(bits > 0 && bits <= 64, "bit width must be between 1 and 64");
+            self.bits = bits;
+            self
+        }
 
-    #[test]
-    fn test_low_bits_of_byte() {
-        for i in 0..127 {
-            assert_eq!(i, low_bits_of_byte(i));
-            assert_eq!(i, low_bits_of_byte(i | CONTINUATION_BIT));
+        /// The maximum number of elements that `ragged_unsigned` and
+        /// `byte_values` may produce, counting each row of a ragged array as
+        /// an element, beyond which an `Error::LimitExceeded` is returned, or
+        /// `None` for no limit. These decoders do not recurse, so there is no
+        /// separate nesting depth limit.
+        pub fn max_elements(mut self, max_elements: Option<usize>) -> Self {
+            self.max_elements = max_elements;
+            self
         }
-    }
 
-    #[test]
-    fn test_low_bits_of_u64() {
-        for i in 0u64..127 {
-            assert_eq!(i as u8, low_bits_of_u64(1 << 16 | i));
-            assert_eq!(i as u8,
-                       low_bits_of_u64(i << 16 | i | (CONTINUATION_BIT as u64)));
+        /// Read an unsigned LEB128 number from the given `std::io::Read`able
+        /// according to this policy, and return it or an error if reading
+        /// failed or the number violates the policy.
+        pub fn unsigned<R>(&self, r: &mut R) -> Result<u64, Error>
+            where R: ?Sized + io::Read
+        {
+            let mut buf = [0; max_bytes(64)];
+            let len = self.read_encoding(r, &mut buf)?;
+            let val = unsigned(&mut &buf[..len])?;
+
+            if self.bits < 64 && val >> self.bits != 0 {
+                return Err(Error::Overflow);
+            }
+            if self.strict && len > unsigned_len(val) {
+                return Err(Error::NonCanonical);
+            }
+            Ok(val)
         }
-    }
 
-    // Examples from the DWARF 4 standard, section 7.6, figure 22.
-    #[test]
-    fn test_read_unsigned() {
-        let buf = [2u8];
-        let mut readable = &buf[..];
-        assert_eq!(2,
-                   read::unsigned(&mut readable).expect("Should read number"));
+        /// Read a signed LEB128 number from the given `std::io::Read`able
+        /// according to this policy, and return it or an error if reading
+        /// failed or the number violates the policy.
+        pub fn signed<R>(&self, r: &mut R) -> Result<i64, Error>
+            where R: ?Sized + io::Read
+        {
+            let mut buf = [0; max_bytes(64)];
+            let len = self.read_encoding(r, &mut buf)?;
+            let val = signed(&mut &buf[..len])?;
 
-        let buf = [127u8];
-        let mut readable = &buf[..];
-        assert_eq!(127,
-                   read::unsigned(&mut readable).expect("Should read number"));
+            // Every bit from the sign bit of the width upwards must agree.
+            let high_bits = val >> (self.bits - 1);
+            if high_bits != 0 && high_bits != -1 {
+                return Err(Error::Overflow);
+            }
+            if self.strict && len > signed_len(val) {
+                return Err(Error::NonCanonical);
+            }
+            Ok(val)
+        }
 
-        let buf = [CONTINUATION_BIT, 1];
-        let mut readable = &buf[..];
-        assert_eq!(128,
-                   read::unsigned(&mut readable).expect("Should read number"));
+        /// Read a ragged two-dimensional array of unsigned LEB128 numbers, as
+        /// `leb128::read::ragged_unsigned` does, according to this policy.
+        pub fn ragged_unsigned<R>(&self, r: &mut R) -> Result<Vec<Vec<u64>>, Error>
+            where R: ?Sized + io::Read
+        {
+            ragged(r, None, self.max_elements, |r| self.unsigned(r))
+        }
 
-        let buf = [1u8 | CONTINUATION_BIT, 1];
-        let mut readable = &buf[..];
-        assert_eq!(129,
-                   read::unsigned(&mut readable).expect("Should read number"));
+        /// Read a count prefixed sequence of byte values, as
+        /// `leb128::read::byte_values` does, according to this policy.
+        pub fn byte_values<R>(&self, r: &mut R) -> Result<Vec<u8>, Error>
+            where R: ?Sized + io::Read
+        {
+            let count = self.unsigned(r)?;
+            if let Some(max) = self.max_elements {
+                if count > max as u64 {
+                    return Err(Error::LimitExceeded);
+                }
+            }
 
-        let buf = [2u8 | CONTINUATION_BIT, 1];
-        let mut readable = &buf[..];
-        assert_eq!(130,
-                   read::unsigned(&mut readable).expect("Should read number"));
+            let mut bytes = vec![];
+            for _ in 0..count {
+                bytes.push(u8::try_from(self.unsigned(r)?).map_err(|_| Error::Overflow)?);
+            }
+            Ok(bytes)
+        }
 
-        let buf = [57u8 | CONTINUATION_BIT, 100];
-        let mut readable = &buf[..];
-        assert_eq!(12857,
-                   read::unsigned(&mut readable).expect("Should read number"));
+        /// Read the bytes of one encoded number into `buf`, enforcing the byte
+        /// limit, and return how many were read.
+        fn read_encoding<R>(&self, r: &mut R, buf: &mut [u8]) -> Result<usize, Error>
+            where R: ?Sized + io::Read
+        {
+            let mut len = 0;
+            loop {
+                if self.max_bytes == Some(len) {
+                    return Err(Error::LimitExceeded);
+                }
+                if len == buf.len() {
+                    return Err(Error::Overflow);
+                }
+
+                r.read_exact(&mut buf[len..len + 1])?;
+                len += 1;
+
+                if buf[len - 1] & CONTINUATION_BIT == 0 {
+                    return Ok(len);
+                }
+            }
+        }
     }
 
-    // Examples from the DWARF 4 standard, section 7.6, figure 23.
-    #[test]
-    fn test_read_signed() {
-        let buf = [2u8];
-        let mut readable = &buf[..];
-        assert_eq!(2, read::signed(&mut readable).expect("Should read number"));
+    /// Scan the given buffer of concatenated LEB128 numbers and return the
+    /// offset at which each number starts, or an error if the last number is
+    /// truncated.
+    ///
+    /// Only the continuation bits are inspected, so the numbers themselves
+    /// are not decoded and may still overflow when read.
+    pub fn boundaries(buf: &[u8]) -> Result<Vec<usize>, Error> {
+        let mut offsets = vec![];
+        let mut start = true;
 
-        let buf = [0x7eu8];
-        let mut readable = &buf[..];
-        assert_eq!(-2, read::signed(&mut readable).expect("Should read number"));
+        for (i, byte) in buf.iter().enumerate() {
+            if start {
+                offsets.push(i);
+            }
+            start = byte & CONTINUATION_BIT == 0;
+        }
 
-        let buf = [127u8 | CONTINUATION_BIT, 0];
-        let mut readable = &buf[..];
-        assert_eq!(127,
-                   read::signed(&mut readable).expect("Should read number"));
+        if !start {
+            return Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                     "truncated LEB128 number")));
+        }
 
-        let buf = [1u8 | CONTINUATION_BIT, 0x7f];
-        let mut readable = &buf[..];
-        assert_eq!(-127,
-                   read::signed(&mut readable).expect("Should read number"));
+        Ok(offsets)
+    }
 
-        let buf = [CONTINUATION_BIT, 1];
-        let mut readable = &buf[..];
-        assert_eq!(128,
-                   read::signed(&mut readable).expect("Should read number"));
+    /// Split the given buffer of concatenated LEB128 numbers into one slice
+    /// per number, or return an error if the last number is truncated.
+    ///
+    /// Like `boundaries`, only the continuation bits are inspected, so the
+    /// numbers themselves are not decoded and may still overflow when read.
+    ///
+    /// ```
+    /// let buf = [0x02, 0x80, 0x01, 0x7f];
+    /// assert_eq!(leb128::read::split(&buf).unwrap(), [&[0x02][..], &[0x80, 0x01], &[0x7f]]);
+    /// ```
+    pub fn split(buf: &[u8]) -> Result<Vec<&[u8]>, Error> {
+        split_n(buf, usize::MAX)
+    }
 
-        let buf = [CONTINUATION_BIT, 0x7f];
-        let mut readable = &buf[..];
-        assert_eq!(-128,
-                   read::signed(&mut readable).expect("Should read number"));
+    /// Split at most the first `n` numbers of the given buffer of concatenated
+    /// LEB128 numbers into one slice per number, or return an error if one of
+    /// them is truncated. Bytes after the `n`th number are not inspected.
+    pub fn split_n(buf: &[u8], n: usize) -> Result<Vec<&[u8]>, Error> {
+        let mut slices = vec![];
+        let mut rest = buf;
 
-        let buf = [1u8 | CONTINUATION_BIT, 1];
-        let mut readable = &buf[..];
-        assert_eq!(129,
-                   read::signed(&mut readable).expect("Should read number"));
+        while slices.len() < n && !rest.is_empty() {
+            let len = match rest.iter().position(|byte| byte & CONTINUATION_BIT == 0) {
+                Some(last) => last + 1,
+                None => {
+                    return Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                             "truncated LEB128 number")))
+                }
+            };
+            let (slice, tail) = rest.split_at(len);
+            slices.push(slice);
+            rest = tail;
+        }
 
-        let buf = [0x7fu8 | CONTINUATION_BIT, 0x7e];
-        let mut readable = &buf[..];
-        assert_eq!(-129,
-                   read::signed(&mut readable).expect("Should read number"));
+        Ok(slices)
     }
 
-    #[test]
-    fn test_read_signed_63_bits() {
-        let buf = [CONTINUATION_BIT,
-                   CONTINUATION_BIT,
+    /// A LEB128 number that has been located in a buffer but not decoded yet.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct LazyValue<'a> {
+        bytes: &'a [u8],
+    }
+
+    impl<'a> LazyValue<'a> {
+        /// Decode the number as unsigned, or return an error if it overflows.
+        pub fn get_u64(&self) -> Result<u64, Error> {
+            unsigned(&mut &self.bytes[..])
+        }
+
+        /// Decode the number as signed, or return an error if it overflows.
+        pub fn get_i64(&self) -> Result<i64, Error> {
+            signed(&mut &self.bytes[..])
+        }
+
+        /// Return the bytes that encode the number.
+        pub fn as_bytes(&self) -> &'a [u8] {
+            self.bytes
+        }
+    }
+
+    /// Locate the LEB128 number at the start of `buf` without decoding it, and
+    /// return it along with the rest of `buf`, or an error if the number is
+    /// truncated.
+    ///
+    /// Only the continuation bits are inspected, so skipping numbers this way
+    /// is cheaper than reading them, and decoding is deferred until
+    /// `LazyValue::get_u64` or `LazyValue::get_i64` is called.
+    ///
+    /// ```
+    /// let buf = [0x80, 0x01, 0x7f];
+    /// let (first, rest) = leb128::read::lazy(&buf).unwrap();
+    /// let (second, _) = leb128::read::lazy(rest).unwrap();
+    /// assert_eq!(first.as_bytes(), [0x80, 0x01]);
+    /// assert_eq!(second.get_i64().unwrap(), -1);
+    /// ```
+    pub fn lazy(buf: &[u8]) -> Result<(LazyValue<'_>, &[u8]), Error> {
+        match buf.iter().position(|byte| byte & CONTINUATION_BIT == 0) {
+            Some(last) => {
+                let (bytes, rest) = buf.split_at(last + 1);
+                Ok((LazyValue { bytes }, rest))
+            }
+            None => {
+                Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                  "truncated LEB128 number")))
+            }
+        }
+    }
+
+    /// A buffer of concatenated unsigned LEB128 numbers, indexed by where each
+    /// number starts so that any one of them can be decoded on its own.
+    ///
+    /// ```
+    /// let buf = [0x02, 0x80, 0x01, 0xe5, 0x8e, 0x26];
+    /// let table = leb128::read::SearchableTable::new(&buf).unwrap();
+    /// assert_eq!(table.get(1).unwrap(), 128);
+    /// assert_eq!(table.binary_search(128).unwrap(), Ok(1));
+    /// assert_eq!(table.binary_search(129).unwrap(), Err(2));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct SearchableTable<'a> {
+        buf: &'a [u8],
+        offsets: Vec<usize>,
+    }
+
+    impl<'a> SearchableTable<'a> {
+        /// Index the numbers in `buf` with `boundaries`, or return an error if
+        /// the last number is truncated.
+        pub fn new(buf: &'a [u8]) -> Result<SearchableTable<'a>, Error> {
+            let offsets = boundaries(buf)?;
+            Ok(SearchableTable { buf, offsets })
+        }
+
+        /// Return the number of numbers in the table.
+        pub fn len(&self) -> usize {
+            self.offsets.len()
+        }
+
+        /// Return `true` if the table holds no numbers.
+        pub fn is_empty(&self) -> bool {
+            self.offsets.is_empty()
+        }
+
+        /// Decode the `i`th number, or return an error if it overflows.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `i` is not less than `len()`.
+        pub fn get(&self, i: usize) -> Result<u64, Error> {
+            unsigned(&mut &self.buf[self.offsets[i]..])
+        }
+
+        /// Binary search the table for `target`, decoding only the numbers
+        /// that are probed. The numbers must be sorted in increasing order.
+        ///
+        /// Like `slice::binary_search`, returns `Ok` with the index of a
+        /// matching number, or `Err` with the index where `target` could be
+        /// inserted to keep the order. Returns an outer error if a probed
+        /// number overflows.
+        pub fn binary_search(&self, target: u64) -> Result<Result<usize, usize>, Error> {
+            let (mut low, mut high) = (0, self.len());
+            while low < high {
+                let mid = low + (high - low) / 2;
+                let val = self.get(mid)?;
+                if val < target {
+                    low = mid + 1;
+                } else if val > target {
+                    high = mid;
+                } else {
+                    return Ok(Ok(mid));
+                }
+            }
+            Ok(Err(low))
+        }
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// pass it to `f`, returning its result, or an error if reading failed.
+    ///
+    /// ```
+    /// let pool = ["zero", "one", "two"];
+    /// let mut readable = &[2u8][..];
+    /// let s = leb128::read::unsigned_then(&mut readable, |i| {
+    ///     pool.get(i as usize).ok_or(leb128::read::Error::Overflow)
+    /// });
+    /// assert_eq!(*s.unwrap(), "two");
+    /// ```
+    pub fn unsigned_then<R, T, F>(r: &mut R, f: F) -> Result<T, Error>
+        where R: ?Sized + io::Read,
+              F: FnOnce(u64) -> Result<T, Error>
+    {
+        f(unsigned(r)?)
+    }
+
+    /// Read a `char` encoded as the unsigned LEB128 number of its Unicode
+    /// scalar value from the given `std::io::Read`able and return it, or an
+    /// `Error::InvalidChar` if the number is not a scalar value, or an error if
+    /// reading failed.
+    pub fn char<R>(r: &mut R) -> Result<char, Error>
+        where R: ?Sized + io::Read
+    {
+        let val = unsigned(r)?;
+        if val > u32::MAX as u64 {
+            return Err(Error::InvalidChar(val));
+        }
+        ::std::char::from_u32(val as u32).ok_or(Error::InvalidChar(val))
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// convert it to `T`, or return an `Error::Overflow` if it does not fit in
+    /// `T`, or an error if reading failed.
+    ///
+    /// ```
+    /// let mut readable = &[0x80, 0x02, 0x80, 0x04][..];
+    /// assert_eq!(leb128::read::unsigned_as::<u8, _>(&mut readable).ok(), None);
+    /// assert_eq!(leb128::read::unsigned_as::<u16, _>(&mut readable).unwrap(), 512);
+    /// ```
+    pub fn unsigned_as<T, R>(r: &mut R) -> Result<T, Error>
+        where T: TryFrom<u64>,
+              R: ?Sized + io::Read
+    {
+        T::try_from(unsigned(r)?).map_err(|_| Error::Overflow)
+    }
+
+    /// Read a signed LEB128 number from the given `std::io::Read`able and
+    /// convert it to `T`, or return an `Error::Overflow` if it does not fit in
+    /// `T`, or an error if reading failed.
+    pub fn signed_as<T, R>(r: &mut R) -> Result<T, Error>
+        where T: TryFrom<i64>,
+              R: ?Sized + io::Read
+    {
+        T::try_from(signed(r)?).map_err(|_| Error::Overflow)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it, or an `Error::UnexpectedZero` if it is zero, or an error if
+    /// reading failed.
+    pub fn nonzero_u64<R>(r: &mut R) -> Result<NonZeroU64, Error>
+        where R: ?Sized + io::Read
+    {
+        NonZeroU64::new(unsigned(r)?).ok_or(Error::UnexpectedZero)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it, or an `Error::UnexpectedZero` if it is zero, or an
+    /// `Error::Overflow` if it does not fit in 32 bits, or an error if reading
+    /// failed.
+    pub fn nonzero_u32<R>(r: &mut R) -> Result<NonZeroU32, Error>
+        where R: ?Sized + io::Read
+    {
+        NonZeroU32::new(unsigned_as(r)?).ok_or(Error::UnexpectedZero)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// map it to a `T` with `f`, typically to decode the tag of an enum.
+    /// Returns an `Error::UnknownDiscriminant` if `f` returns `None`, or an
+    /// error if reading failed.
+    ///
+    /// ```
+    /// #[derive(Debug, PartialEq)]
+    /// enum Shape { Circle, Square }
+    ///
+    /// let shape = |tag| match tag {
+    ///     0 => Some(Shape::Circle),
+    ///     1 => Some(Shape::Square),
+    ///     _ => None,
+    /// };
+    /// let mut readable = &[0x01, 0x02][..];
+    /// assert_eq!(leb128::read::discriminant(&mut readable, shape).unwrap(), Shape::Square);
+    /// assert!(leb128::read::discriminant(&mut readable, shape).is_err());
+    /// ```
+    pub fn discriminant<R, T, F>(r: &mut R, f: F) -> Result<T, Error>
+        where R: ?Sized + io::Read,
+              F: FnOnce(u64) -> Option<T>
+    {
+        let val = unsigned(r)?;
+        f(val).ok_or(Error::UnknownDiscriminant(val))
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it, or an `Error::Overflow` if it is greater than 255, or an
+    /// error if reading failed.
+    ///
+    /// Numbers from 128 to 255 are encoded in two bytes, so a byte sized
+    /// number is not necessarily a single encoded byte.
+    pub fn u8<R>(r: &mut R) -> Result<u8, Error>
+        where R: ?Sized + io::Read
+    {
+        unsigned_as(r)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it, or an `Error::Overflow` if it does not fit in 24 bits, or an
+    /// error if reading failed.
+    pub fn unsigned_u24<R>(r: &mut R) -> Result<u32, Error>
+        where R: ?Sized + io::Read
+    {
+        let val = unsigned(r)?;
+        if val >> 24 != 0 {
+            return Err(Error::Overflow);
+        }
+        Ok(val as u32)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it, or an `Error::Overflow` if it does not fit in 48 bits, or an
+    /// error if reading failed.
+    pub fn unsigned_u48<R>(r: &mut R) -> Result<u64, Error>
+        where R: ?Sized + io::Read
+    {
+        let val = unsigned(r)?;
+        if val >> 48 != 0 {
+            return Err(Error::Overflow);
+        }
+        Ok(val)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// check that it is equal to `expected`. Returns an `Error::Mismatch` if
+    /// it is not, or an error if reading failed.
+    ///
+    /// This is useful for magic numbers and version fields.
+    pub fn expect_unsigned<R>(r: &mut R, expected: u64) -> Result<(), Error>
+        where R: ?Sized + io::Read
+    {
+        let found = unsigned(r)?;
+        if found != expected {
+            return Err(Error::Mismatch { expected, found });
+        }
+        Ok(())
+    }
+
+    /// Return whether the given buffer starts with a complete LEB128 number,
+    /// that is, whether a byte without the continuation bit appears within
+    /// the first ten bytes, the most a 64-bit number can occupy.
+    ///
+    /// This does not decode the number, so reading it may still overflow.
+    pub fn has_complete_value(buf: &[u8]) -> bool {
+        buf.iter().take(max_bytes(64)).any(|byte| byte & CONTINUATION_BIT == 0)
+    }
+
+    /// Return whether the unsigned LEB128 numbers at the start of `a` and `b`
+    /// are equal, or an error if either buffer does not start with a complete
+    /// number.
+    ///
+    /// The numbers are compared group by group without being decoded, so
+    /// padded and canonical encodings of the same number compare equal, and
+    /// numbers too large for a `u64` can be compared too.
+    pub fn unsigned_eq_bytes(a: &[u8], b: &[u8]) -> Result<bool, Error> {
+        fn encoded_len(buf: &[u8]) -> Result<usize, Error> {
+            match buf.iter().position(|byte| byte & CONTINUATION_BIT == 0) {
+                Some(i) => Ok(i + 1),
+                None => {
+                    Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                      "truncated LEB128 number")))
+                }
+            }
+        }
+
+        let a = &a[..encoded_len(a)?];
+        let b = &b[..encoded_len(b)?];
+        for i in 0..a.len().max(b.len()) {
+            // The shorter encoding is implicitly padded with zero groups.
+            let group_a = a.get(i).map_or(0, |&byte| low_bits_of_byte(byte));
+            let group_b = b.get(i).map_or(0, |&byte| low_bits_of_byte(byte));
+            if group_a != group_b {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Read a protobuf-style tag followed by an unsigned LEB128 value from the
+    /// given `std::io::Read`able. The tag is itself an unsigned LEB128 number
+    /// holding `(field << 3) | wire_type`. Returns `(field, wire_type, value)`
+    /// or an error if reading failed.
+    pub fn tagged_unsigned<R>(r: &mut R) -> Result<(u32, u8, u64), Error>
+        where R: ?Sized + io::Read
+    {
+        let tag = unsigned(r)?;
+        if tag >> 3 > u32::MAX as u64 {
+            return Err(Error::Overflow);
+        }
+
+        let field = (tag >> 3) as u32;
+        let wire_type = (tag & 0x07) as u8;
+        let val = unsigned(r)?;
+        Ok((field, wire_type, val))
+    }
+
+    /// Read a signed number that was zigzag encoded and then written as
+    /// unsigned LEB128 from the given `std::io::Read`able, and return it or an
+    /// error if reading failed.
+    pub fn zigzag<R>(r: &mut R) -> Result<i64, Error>
+        where R: ?Sized + io::Read
+    {
+        let val = unsigned(r)?;
+        Ok((val >> 1) as i64 ^ -((val & 1) as i64))
+    }
+
+    /// Read an `i32` written by .NET's `BinaryWriter.Write7BitEncodedInt` from
+    /// the given `std::io::Read`able, and return it or an error if reading
+    /// failed.
+    ///
+    /// The number is the unsigned LEB128 encoding of the `i32` reinterpreted as
+    /// a `u32`. Like .NET's `BinaryReader.Read7BitEncodedInt`, at most five
+    /// bytes are read, and a fifth byte greater than `0x0f` is an
+    /// `Error::Overflow`.
+    pub fn net_7bit_i32<R>(r: &mut R) -> Result<i32, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut result = 0u32;
+        for shift in (0..28).step_by(7) {
+            let mut buf = [0];
+            r.read_exact(&mut buf)?;
+            result |= (low_bits_of_byte(buf[0]) as u32) << shift;
+            if buf[0] & CONTINUATION_BIT == 0 {
+                return Ok(result as i32);
+            }
+        }
+
+        let mut buf = [0];
+        r.read_exact(&mut buf)?;
+        if buf[0] > 0x0f {
+            return Err(Error::Overflow);
+        }
+        Ok((result | (buf[0] as u32) << 28) as i32)
+    }
+
+    /// Read a signed number written as a sign byte, `0` for positive or `1`
+    /// for negative, followed by the unsigned LEB128 encoding of its magnitude
+    /// from the given `std::io::Read`able, and return it or an error if reading
+    /// failed. A sign byte other than `0` or `1` is an `InvalidData` IO error,
+    /// raised before the magnitude is read, and a magnitude that does not fit
+    /// in an `i64` is an `Error::Overflow`. Negative zero, which
+    /// `write::sign_magnitude` never produces, is an `InvalidData` IO error.
+    pub fn sign_magnitude<R>(r: &mut R) -> Result<i64, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut sign = [0];
+        r.read_exact(&mut sign)?;
+        if sign[0] > 1 {
+            return Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                     "sign byte must be 0 or 1")));
+        }
+
+        let magnitude = unsigned(r)?;
+        match (sign[0], magnitude) {
+            (0, _) => i64::try_from(magnitude).map_err(|_| Error::Overflow),
+            (_, 0) => {
+                Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                  "negative zero")))
+            }
+            // The magnitude of `i64::MIN` is one more than `i64::MAX`, and
+            // wraps around to itself when negated.
+            (_, magnitude) if magnitude <= 1 << 63 => Ok((magnitude as i64).wrapping_neg()),
+            _ => Err(Error::Overflow),
+        }
+    }
+
+    /// Read a tag byte from the given `std::io::Read`able, followed by an
+    /// unsigned LEB128 number if the tag is `0` or a signed one if it is `1`,
+    /// and return the number widened to an `i128`, or an error if reading
+    /// failed. Any other tag is an `Error::UnknownDiscriminant`.
+    ///
+    /// ```
+    /// let mut readable = &[0x00, 0xff, 0x01, 0x01, 0x7f][..];
+    /// assert_eq!(leb128::read::tagged_integer(&mut readable).unwrap(), 255);
+    /// assert_eq!(leb128::read::tagged_integer(&mut readable).unwrap(), -1);
+    /// ```
+    pub fn tagged_integer<R>(r: &mut R) -> Result<i128, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut tag = [0];
+        r.read_exact(&mut tag)?;
+        match tag[0] {
+            0 => Ok(unsigned(r)? as i128),
+            1 => Ok(signed(r)? as i128),
+            tag => Err(Error::UnknownDiscriminant(tag as u64)),
+        }
+    }
+
+    /// Read an unsigned 128-bit LEB128 number from the given `std::io::Read`able
+    /// and return it as its `(low, high)` 64-bit halves, or an error if reading
+    /// failed. Only 64-bit arithmetic is used, for targets where `u128` is
+    /// unavailable or slow.
+    pub fn unsigned_u128_halves<R>(r: &mut R) -> Result<(u64, u64), Error>
+        where R: ?Sized + io::Read
+    {
+        let mut low = 0;
+        let mut high = 0;
+        let mut shift = 0;
+
+        loop {
+            let mut buf = [0];
+            r.read_exact(&mut buf)?;
+            let byte = buf[0];
+
+            // The nineteenth byte holds only bits 126 and 127.
+            if shift == 126 && byte > 0x03 {
+                return Err(Error::Overflow);
+            }
+
+            let low_bits = low_bits_of_byte(byte) as u64;
+            if shift < 64 {
+                low |= low_bits << shift;
+                if shift > 57 {
+                    // Carry the bits that do not fit in the low half.
+                    high |= low_bits >> (64 - shift);
+                }
+            } else {
+                high |= low_bits << (shift - 64);
+            }
+
+            if byte & CONTINUATION_BIT == 0 {
+                return Ok((low, high));
+            }
+
+            shift += 7;
+        }
+    }
+
+    /// Read an unsigned LEB128 number of any size from the given
+    /// `std::io::Read`able and return it, or an error if reading failed.
+    ///
+    /// Since there is no width to overflow, `max_bytes` is the only guard
+    /// against unbounded input: if it is given, an encoding longer than that
+    /// is an `Error::LimitExceeded`.
+    #[cfg(feature = "bigint")]
+    pub fn big_unsigned<R>(r: &mut R, max_bytes: Option<usize>) -> Result<BigUint, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut digits = vec![];
+        loop {
+            if max_bytes == Some(digits.len()) {
+                return Err(Error::LimitExceeded);
+            }
+
+            let mut buf = [0];
+            r.read_exact(&mut buf)?;
+            digits.push(low_bits_of_byte(buf[0]));
+
+            if buf[0] & CONTINUATION_BIT == 0 {
+                break;
+            }
+        }
+
+        // Each byte is a base 128 digit, least significant first.
+        Ok(BigUint::from_radix_le(&digits, 128).expect("every digit is below 128"))
+    }
+
+    /// Read an unsigned LEB128 number of any size from the given
+    /// `std::io::Read`able. Returns `Ok(Ok(val))` if the number fits in a
+    /// `u64`, `Ok(Err(bytes))` with the bytes that encode it if it does not,
+    /// or an error if reading failed.
+    ///
+    /// Over-long encodings padded with zero groups still count as fitting, so
+    /// only the value decides which way the number is returned.
+    ///
+    /// As with `big_unsigned`, `max_bytes` guards against unbounded input: if
+    /// it is given, an encoding longer than that is an `Error::LimitExceeded`.
+    ///
+    /// ```
+    /// let buf = [0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x03];
+    /// assert_eq!(leb128::read::unsigned_or_bytes(&mut &buf[..], Some(16)).unwrap(),
+    ///            Err(buf.to_vec()));
+    /// assert_eq!(leb128::read::unsigned_or_bytes(&mut &[0x7f][..], Some(16)).unwrap(),
+    ///            Ok(127));
+    /// ```
+    pub fn unsigned_or_bytes<R>(r: &mut R,
+                                max_bytes: Option<usize>)
+                                -> Result<Result<u64, Vec<u8>>, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut bytes = vec![];
+        let mut result: u64 = 0;
+        let mut fits = true;
+        let mut shift: u32 = 0;
+
+        loop {
+            if max_bytes == Some(bytes.len()) {
+                return Err(Error::LimitExceeded);
+            }
+
+            let mut buf = [0];
+            r.read_exact(&mut buf)?;
+            bytes.push(buf[0]);
+
+            let low_bits = low_bits_of_byte(buf[0]) as u64;
+            if shift < 64 {
+                result |= low_bits << shift;
+                // Any bits shifted out did not fit.
+                fits &= shift == 0 || low_bits >> (64 - shift) == 0;
+            } else {
+                fits &= low_bits == 0;
+            }
+
+            if buf[0] & CONTINUATION_BIT == 0 {
+                return Ok(if fits { Ok(result) } else { Err(bytes) });
+            }
+
+            // Past bit 63 the shift only needs to stay at least 64.
+            shift = shift.saturating_add(7);
+        }
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it modulo `2^bits`, or an error if reading failed.
+    ///
+    /// Unlike `Options::bits`, which rejects numbers wider than `bits` with an
+    /// `Error::Overflow`, the bits above `bits` are discarded, however many
+    /// bytes the number takes. This matches producers that rely on numbers
+    /// wrapping around at a fixed width.
+    ///
+    /// ```
+    /// let buf = [0xac, 0x02];
+    /// assert_eq!(leb128::read::unsigned_wrapping(&mut &buf[..], 8).unwrap(), 44);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bits` is not between 1 and 64.
+    pub fn unsigned_wrapping<R>(r: &mut R, bits: u32) -> Result<u64, Error>
+        where R: ?Sized + io::Read
+    {
+        assert!(bits > 0 && bits <= 64, "bit width must be between 1 and 64");
+
+        let mut result: u64 = 0;
+        let mut shift: u32 = 0;
+        loop {
+            let mut buf = [0];
+            r.read_exact(&mut buf)?;
+
+            if shift < 64 {
+                result |= (low_bits_of_byte(buf[0]) as u64) << shift;
+            }
+
+            if buf[0] & CONTINUATION_BIT == 0 {
+                return Ok(if bits == 64 { result } else { result & ((1 << bits) - 1) });
+            }
+
+            // Past bit 63 the shift only needs to stay at least 64.
+            shift = shift.saturating_add(7);
+        }
+    }
+
+    /// Read the nanoseconds part of a `Duration` or `SystemTime`.
+    fn subsec_nanos<R>(r: &mut R) -> Result<u32, Error>
+        where R: ?Sized + io::Read
+    {
+        let nanos = unsigned(r)?;
+        if nanos >= 1_000_000_000 {
+            return Err(Error::Overflow);
+        }
+        Ok(nanos as u32)
+    }
+
+    /// Read a `Duration` written as the unsigned LEB128 numbers of its whole
+    /// seconds and of its additional nanoseconds from the given
+    /// `std::io::Read`able, and return it or an error if reading failed. A
+    /// nanoseconds part of a second or more is an `Error::Overflow`.
+    pub fn duration<R>(r: &mut R) -> Result<Duration, Error>
+        where R: ?Sized + io::Read
+    {
+        let secs = unsigned(r)?;
+        let nanos = subsec_nanos(r)?;
+        Ok(Duration::new(secs, nanos))
+    }
+
+    /// Read a `SystemTime` written as the signed LEB128 number of whole
+    /// seconds since `UNIX_EPOCH`, rounded down, and the unsigned LEB128
+    /// number of additional nanoseconds from the given `std::io::Read`able,
+    /// and return it or an error if reading failed. A nanoseconds part of a
+    /// second or more, or a time that the platform cannot represent, is an
+    /// `Error::Overflow`.
+    pub fn system_time<R>(r: &mut R) -> Result<SystemTime, Error>
+        where R: ?Sized + io::Read
+    {
+        let secs = signed(r)?;
+        let nanos = Duration::from_nanos(subsec_nanos(r)? as u64);
+        let time = if secs >= 0 {
+            UNIX_EPOCH.checked_add(Duration::from_secs(secs as u64))
+        } else {
+            UNIX_EPOCH.checked_sub(Duration::from_secs(secs.unsigned_abs()))
+        };
+        time.and_then(|time| time.checked_add(nanos)).ok_or(Error::Overflow)
+    }
+
+    /// Read a `SocketAddr` written as a version byte, `4` or `6`, the octets of
+    /// the IP address, and the unsigned LEB128 number of the port from the
+    /// given `std::io::Read`able, and return it or an error if reading failed.
+    ///
+    /// Any other version byte is an `InvalidData` IO error, and a port that
+    /// does not fit in 16 bits is an `Error::Overflow`.
+    pub fn socket_addr<R>(r: &mut R) -> Result<SocketAddr, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut version = [0];
+        r.read_exact(&mut version)?;
+        let ip = match version[0] {
+            4 => {
+                let mut octets = [0; 4];
+                r.read_exact(&mut octets)?;
+                Ipv4Addr::from(octets).into()
+            }
+            6 => {
+                let mut octets = [0; 16];
+                r.read_exact(&mut octets)?;
+                Ipv6Addr::from(octets).into()
+            }
+            _ => {
+                return Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                         "IP version must be 4 or 6")))
+            }
+        };
+        let port = unsigned_as(r)?;
+        Ok(SocketAddr::new(ip, port))
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// return it together with the raw bytes that encoded it, or an error if
+    /// reading failed. Any redundant padding bytes are preserved.
+    pub fn unsigned_with_bytes<R>(r: &mut R) -> Result<(u64, Vec<u8>), Error>
+        where R: ?Sized + io::Read
+    {
+        let mut bytes = vec![];
+        let val = unsigned(&mut Tee { r, w: &mut bytes })?;
+        Ok((val, bytes))
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able,
+    /// copying every byte read to the given `std::io::Write`able as it goes,
+    /// and return the number or an error if reading or writing failed.
+    ///
+    /// The bytes read are forwarded even if the number turns out to be
+    /// invalid, so on an overflow `w` has received exactly the bytes read up
+    /// to and including the one that overflowed.
+    pub fn unsigned_tee<R, W>(r: &mut R, w: &mut W) -> Result<u64, Error>
+        where R: ?Sized + io::Read,
+              W: ?Sized + io::Write
+    {
+        unsigned(&mut Tee { r, w })
+    }
+
+    /// Read a signed LEB128 number from the given `std::io::Read`able, copying
+    /// every byte read to the given `std::io::Write`able as it goes, and return
+    /// the number or an error if reading or writing failed.
+    ///
+    /// The bytes read are forwarded even if the number turns out to be
+    /// invalid, so on an overflow `w` has received exactly the bytes read up
+    /// to and including the one that overflowed.
+    pub fn signed_tee<R, W>(r: &mut R, w: &mut W) -> Result<i64, Error>
+        where R: ?Sized + io::Read,
+              W: ?Sized + io::Write
+    {
+        signed(&mut Tee { r, w })
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able, and
+    /// return it along with the range of offsets its bytes occupied, counting
+    /// from `start_offset` for the first byte, or an error if reading failed.
+    ///
+    /// ```
+    /// let mut readable = &[0x80, 0x01, 0x02][..];
+    /// assert_eq!(leb128::read::unsigned_span(&mut readable, 40).unwrap(), (128, 40..42));
+    /// assert_eq!(leb128::read::unsigned_span(&mut readable, 42).unwrap(), (2, 42..43));
+    /// ```
+    pub fn unsigned_span<R>(r: &mut R, start_offset: usize) -> Result<(u64, Range<usize>), Error>
+        where R: ?Sized + io::Read
+    {
+        let mut counter = CountingWriter::new(io::sink());
+        let val = unsigned_tee(r, &mut counter)?;
+        let end_offset = start_offset + counter.bytes_written() as usize;
+        Ok((val, start_offset..end_offset))
+    }
+
+    /// Read a signed LEB128 number from the given `std::io::Read`able and
+    /// return it together with the width in bits from which it was sign
+    /// extended, which is seven times the number of bytes that encoded it, or
+    /// an error if reading failed.
+    ///
+    /// Re-encoding the number with `leb128::write::signed_fixed_width` at
+    /// `width / 7` bytes reproduces the original encoding, even if it was
+    /// padded.
+    pub fn signed_with_width<R>(r: &mut R) -> Result<(i64, u32), Error>
+        where R: ?Sized + io::Read
+    {
+        let mut counter = CountingWriter::new(io::sink());
+        let val = signed(&mut Tee { r, w: &mut counter })?;
+        Ok((val, counter.bytes_written() as u32 * 7))
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Cursor` and
+    /// return it or an error if reading failed.
+    ///
+    /// The cursor's position is advanced by exactly the number of bytes that
+    /// were consumed, even when an error is returned, so that on failure it
+    /// points just past the last byte that was examined.
+    ///
+    /// ```
+    /// use std::io::Cursor;
+    ///
+    /// let mut cursor = Cursor::new(vec![0x80, 0x01, 0x80]);
+    /// assert_eq!(leb128::read::unsigned_at(&mut cursor).unwrap(), 128);
+    /// assert_eq!(cursor.position(), 2);
+    ///
+    /// // The last number is truncated.
+    /// assert!(leb128::read::unsigned_at(&mut cursor).is_err());
+    /// assert_eq!(cursor.position(), 3);
+    /// ```
+    pub fn unsigned_at<T>(cursor: &mut io::Cursor<T>) -> Result<u64, Error>
+        where T: AsRef<[u8]>
+    {
+        let pos = cursor.position();
+        let (result, consumed) = {
+            let buf = cursor.get_ref().as_ref();
+            let start = if pos < buf.len() as u64 { pos as usize } else { buf.len() };
+            let mut readable = &buf[start..];
+            let len = readable.len();
+            let result = unsigned(&mut readable);
+            (result, len - readable.len())
+        };
+        cursor.set_position(pos + consumed as u64);
+        result
+    }
+
+    /// Read an unsigned LEB128 number from `buf` starting at `*offset`, and
+    /// advance `*offset` past it. Returns the number, or an error if the
+    /// number is truncated by the end of `buf` or is invalid, in which case
+    /// `*offset` is left unchanged.
+    ///
+    /// ```
+    /// let buf = [0x02, 0x80, 0x01];
+    /// let mut offset = 1;
+    /// assert_eq!(leb128::read::unsigned_at_offset(&buf, &mut offset).unwrap(), 128);
+    /// assert_eq!(offset, 3);
+    /// ```
+    pub fn unsigned_at_offset(buf: &[u8], offset: &mut usize) -> Result<u64, Error> {
+        let mut readable = buf.get(*offset..).unwrap_or(&[]);
+        let val = unsigned(&mut readable)?;
+        *offset = buf.len() - readable.len();
+        Ok(val)
+    }
+
+    /// Read a signed LEB128 number from `buf` starting at `*offset`, and
+    /// advance `*offset` past it. Returns the number, or an error if the
+    /// number is truncated by the end of `buf` or is invalid, in which case
+    /// `*offset` is left unchanged.
+    pub fn signed_at_offset(buf: &[u8], offset: &mut usize) -> Result<i64, Error> {
+        let mut readable = buf.get(*offset..).unwrap_or(&[]);
+        let val = signed(&mut readable)?;
+        *offset = buf.len() - readable.len();
+        Ok(val)
+    }
+
+    /// Decode the unsigned LEB128 number at the start of the given
+    /// `std::io::BufRead`able's buffer without consuming it, and return it, or
+    /// `None` if the buffered bytes do not hold a complete number, or an error
+    /// if filling the buffer failed or the number overflows.
+    ///
+    /// `None` is also returned at the end of input. Reading the number with
+    /// `unsigned` afterwards consumes it.
+    ///
+    /// ```
+    /// let mut readable = &[0x80, 0x01, 0x02][..];
+    /// assert_eq!(leb128::read::peek_unsigned(&mut readable).unwrap(), Some(128));
+    /// assert_eq!(leb128::read::unsigned(&mut readable).unwrap(), 128);
+    /// assert_eq!(leb128::read::peek_unsigned(&mut &[0x80][..]).unwrap(), None);
+    /// ```
+    pub fn peek_unsigned<R>(r: &mut R) -> Result<Option<u64>, Error>
+        where R: ?Sized + io::BufRead
+    {
+        let mut buf = r.fill_buf()?;
+        match unsigned(&mut buf) {
+            Ok(val) => Ok(Some(val)),
+            Err(ref e) if e.kind() == ErrorKind::UnexpectedEndOfData => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::BufRead`able
+    /// and return it along with the number of bytes buffered after it, or an
+    /// error if reading failed.
+    ///
+    /// The buffered count comes from `fill_buf`, so if the number used up the
+    /// buffer, the underlying reader is asked for more data, and `0` means the
+    /// end of input. A framer can compare the count against a decoded length
+    /// to decide whether the whole frame is already at hand.
+    ///
+    /// ```
+    /// let mut readable = &[0x03, 0xaa, 0xbb][..];
+    /// let (len, buffered) = leb128::read::unsigned_with_remaining(&mut readable).unwrap();
+    /// assert_eq!((len, buffered), (3, 2));
+    /// ```
+    pub fn unsigned_with_remaining<R>(r: &mut R) -> Result<(u64, usize), Error>
+        where R: ?Sized + io::BufRead
+    {
+        let val = unsigned(r)?;
+        let remaining = r.fill_buf()?.len();
+        Ok((val, remaining))
+    }
+
+    /// The progress made decoding an unsigned LEB128 number one byte at a
+    /// time, so that decoding can be suspended and resumed.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct Decoder {
+        result: u64,
+        shift: u32,
+    }
+
+    impl Decoder {
+        /// Create a decoder that has not seen any byte yet.
+        pub fn new() -> Decoder {
+            Decoder::default()
+        }
+
+        /// Return `true` if part of a number has been decoded.
+        pub fn in_progress(&self) -> bool {
+            self.shift != 0
+        }
+
+        /// Decode the next byte of the number, and return the number if the
+        /// byte completes it, or `None` if more bytes are needed, or an
+        /// `Error::Overflow` if the number does not fit in a `u64`. The decoder
+        /// is ready for a new number after either a number or an error.
+        pub fn push(&mut self, byte: u8) -> Result<Option<u64>, Error> {
+            let more = byte & CONTINUATION_BIT != 0;
+            let next = self.result as u128 | (low_bits_of_byte(byte) as u128) << self.shift;
+
+            // As in `unsigned`, the number overflows if it exceeds `u64::MAX`,
+            // or if more bytes follow the one that reaches bit 63.
+            if next > u64::MAX as u128 || (more && self.shift >= 63) {
+                *self = Decoder::new();
+                return Err(Error::Overflow);
+            }
+
+            if more {
+                self.result = next as u64;
+                self.shift += 7;
+                Ok(None)
+            } else {
+                *self = Decoder::new();
+                Ok(Some(next as u64))
+            }
+        }
+    }
+
+    /// Read an unsigned LEB128 number from the given non-blocking
+    /// `std::io::Read`able, resuming from and saving progress in `state`.
+    ///
+    /// Returns `Ok(Some(val))` once the number is complete, or `Ok(None)` if
+    /// the reader fails with `std::io::ErrorKind::WouldBlock` first, in which
+    /// case calling again with the same `state` picks up where this call left
+    /// off without re-reading any byte. Returns an error if reading failed.
+    ///
+    /// ```
+    /// let mut state = leb128::read::Decoder::new();
+    /// let mut readable = &[0x80][..];
+    /// assert!(leb128::read::unsigned_nonblocking(&mut readable, &mut state).is_err());
+    /// ```
+    pub fn unsigned_nonblocking<R>(r: &mut R, state: &mut Decoder) -> Result<Option<u64>, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut buf = [0];
+        loop {
+            match r.read(&mut buf) {
+                Ok(0) => {
+                    *state = Decoder::new();
+                    return Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                             "failed to fill whole buffer")));
+                }
+                Ok(_) => {
+                    if let Some(val) = state.push(buf[0])? {
+                        return Ok(Some(val));
+                    }
+                }
+                Err(ref e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(Error::IoError(e)),
+            }
+        }
+    }
+
+    /// Read an unsigned LEB128 number from the bytes between `ptr` and `end`,
+    /// and return it along with a pointer to the byte following it, or an
+    /// error if the number is invalid. If the number is not terminated before
+    /// `end`, an `UnexpectedEof` IO error is returned and no byte at or after
+    /// `end` is read.
+    ///
+    /// This is a fast path for parsers that walk memory, such as a mapped
+    /// file, with raw pointers rather than slices.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be less than or equal to `end`, both must be derived from
+    /// the same allocation, and every byte from `ptr` up to but excluding `end`
+    /// must be initialized and valid for reads for the duration of the call.
+    /// There is no alignment requirement.
+    pub unsafe fn unsigned_from_ptr(mut ptr: *const u8,
+                                    end: *const u8)
+                                    -> Result<(u64, *const u8), Error> {
+        let mut result = 0;
+        let mut shift = 0;
+
+        loop {
+            if ptr >= end {
+                return Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                         "truncated LEB128 number")));
+            }
+            let byte = *ptr;
+            ptr = ptr.add(1);
+
+            // The tenth byte holds only bit 63, and must end the number.
+            if shift == 63 && byte > 0x01 {
+                return Err(Error::Overflow);
+            }
+
+            result |= (low_bits_of_byte(byte) as u64) << shift;
+
+            if byte & CONTINUATION_BIT == 0 {
+                return Ok((result, ptr));
+            }
+
+            shift += 7;
+        }
+    }
+
+    /// Read a signed LEB128 number that occupies exactly `bytes` bytes from
+    /// the given `std::io::Read`able and return it, or an error if reading
+    /// failed or the number does not span exactly `bytes` bytes. This is the
+    /// counterpart of `leb128::write::signed_fixed_width`.
+    pub fn signed_fixed_width<R>(r: &mut R, bytes: usize) -> Result<i64, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut field = io::Read::take(r, bytes as u64);
+        let val = signed(&mut field)?;
+        if field.limit() != 0 {
+            return Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                     "number is shorter than its fixed width")));
+        }
+        Ok(val)
+    }
+
+    /// Read an unsigned LEB128 number that occupies exactly the first `len`
+    /// bytes of `buf`, possibly padded with redundant zero bytes, and return
+    /// it. This allows random access to fixed layout fields.
+    ///
+    /// Returns an `UnexpectedEof` IO error if `buf` is shorter than `len`, an
+    /// `InvalidData` IO error if the number ends before its last byte or
+    /// continues past it, or an `Error::Overflow` if it does not fit in a
+    /// `u64`.
+    pub fn unsigned_exact(buf: &[u8], len: usize) -> Result<u64, Error> {
+        if buf.len() < len {
+            return Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                     "buffer is shorter than the field")));
+        }
+
+        let mut field = &buf[..len];
+        match field.last() {
+            Some(byte) if byte & CONTINUATION_BIT == 0 => {}
+            _ => {
+                return Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                         "number continues past its field")))
+            }
+        }
+
+        let val = unsigned(&mut field)?;
+        if !field.is_empty() {
+            return Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                     "number is shorter than its field")));
+        }
+        Ok(val)
+    }
+
+    /// Decode the unsigned LEB128 number at the start of `buf` and return how
+    /// many more bytes its encoding takes than the canonical one, or an error
+    /// if the number cannot be read. Zero means the encoding is canonical, as
+    /// `Options::strict` requires.
+    ///
+    /// ```
+    /// assert_eq!(leb128::read::overlong_bytes(&[0x82, 0x80, 0x00]).unwrap(), 2);
+    /// assert_eq!(leb128::read::overlong_bytes(&[0x80, 0x01]).unwrap(), 0);
+    /// ```
+    pub fn overlong_bytes(buf: &[u8]) -> Result<usize, Error> {
+        let mut readable = buf;
+        let val = unsigned(&mut readable)?;
+        Ok(buf.len() - readable.len() - unsigned_len(val))
+    }
+
+    /// Locate the body of a buffer written by `write::with_length_footer`,
+    /// which ends with the unsigned LEB128 length of the body preceding it, and
+    /// return the body, or an error if the footer is missing or inconsistent.
+    ///
+    /// The body may itself end with bytes that have their high bit set, so
+    /// every possible start of the footer is tried, and the one whose length
+    /// matches its offset is taken. At most one can match.
+    ///
+    /// Returns an `UnexpectedEof` IO error if `buf` does not end with a
+    /// complete number, or an `InvalidData` IO error if no length matches.
+    ///
+    /// ```
+    /// let buf = [b'a', b'b', b'c', 0x03];
+    /// assert_eq!(leb128::read::from_length_footer(&buf).unwrap(), b"abc");
+    /// ```
+    pub fn from_length_footer(buf: &[u8]) -> Result<&[u8], Error> {
+        if buf.last().map_or(true, |byte| byte & CONTINUATION_BIT != 0) {
+            return Err(Error::IoError(io::Error::new(io::ErrorKind::UnexpectedEof,
+                                                     "missing length footer")));
+        }
+
+        // The footer starts right after the last terminating byte before the
+        // final one, or anywhere among the continuation bytes that follow it.
+        let end = buf.len() - 1;
+        let earliest = buf[..end]
+            .iter()
+            .rposition(|byte| byte & CONTINUATION_BIT == 0)
+            .map_or(0, |i| i + 1);
+        for start in (earliest..=end).rev() {
+            match unsigned(&mut &buf[start..]) {
+                Ok(len) if len == start as u64 => return Ok(&buf[..start]),
+                Ok(_) => {}
+                // Longer footers only overflow further.
+                Err(Error::Overflow) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                          "length footer does not match the body")))
+    }
+
+    /// Read unsigned LEB128 numbers from the given `std::io::Read`able until
+    /// it is exhausted and return them all, or an error if reading failed.
+    ///
+    /// Reaching the end of input between two numbers ends the sequence, while
+    /// reaching it in the middle of a number is an error.
+    pub fn unsigned_collect<R>(r: &mut R) -> Result<Vec<u64>, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut vals = vec![];
+        while let Some(byte) = next_byte(r)? {
+            vals.push(unsigned(&mut io::Read::chain(&[byte][..], &mut *r))?);
+        }
+        Ok(vals)
+    }
+
+    /// Read signed LEB128 numbers from the given `std::io::Read`able until it
+    /// is exhausted and return them all, or an error if reading failed.
+    ///
+    /// Reaching the end of input between two numbers ends the sequence, while
+    /// reaching it in the middle of a number is an error.
+    pub fn signed_collect<R>(r: &mut R) -> Result<Vec<i64>, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut vals = vec![];
+        while let Some(byte) = next_byte(r)? {
+            vals.push(signed(&mut io::Read::chain(&[byte][..], &mut *r))?);
+        }
+        Ok(vals)
+    }
+
+    /// Read all the unsigned LEB128 numbers within the limit of the given
+    /// `std::io::Take`, such as a length delimited section, and return them,
+    /// or an error if reading failed.
+    ///
+    /// Reaching the limit between two numbers ends the section, while reaching
+    /// it in the middle of a number is an error.
+    ///
+    /// ```
+    /// use std::io::Read;
+    ///
+    /// let buf = [0x02, 0x80, 0x01, 0x7f];
+    /// let section = (&buf[..]).take(3);
+    /// assert_eq!(leb128::read::from_take(section).unwrap(), [2, 128]);
+    /// ```
+    pub fn from_take<R>(mut r: io::Take<R>) -> Result<Vec<u64>, Error>
+        where R: io::Read
+    {
+        unsigned_collect(&mut r)
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able and
+    /// add it to `acc`, or return an error if reading failed.
+    ///
+    /// Both the number itself and the sum must fit in a `u64`: either one
+    /// overflowing is an `Error::Overflow`, and `acc` is left unchanged.
+    ///
+    /// ```
+    /// let mut readable = &[0x80, 0x01, 0x02][..];
+    /// let mut total = 0;
+    /// leb128::read::unsigned_add_to(&mut readable, &mut total).unwrap();
+    /// leb128::read::unsigned_add_to(&mut readable, &mut total).unwrap();
+    /// assert_eq!(total, 130);
+    /// ```
+    pub fn unsigned_add_to<R>(r: &mut R, acc: &mut u64) -> Result<(), Error>
+        where R: ?Sized + io::Read
+    {
+        let val = unsigned(r)?;
+        *acc = acc.checked_add(val).ok_or(Error::Overflow)?;
+        Ok(())
+    }
+
+    /// Read unsigned LEB128 numbers from the given `std::io::Read`able until
+    /// it is exhausted, folding each into an accumulator starting at `init`
+    /// with `f`, and return the final accumulator, or an error if reading
+    /// failed.
+    ///
+    /// Reaching the end of input between two numbers ends the sequence, while
+    /// reaching it in the middle of a number is an error.
+    pub fn unsigned_fold<R, B, F>(r: &mut R, init: B, mut f: F) -> Result<B, Error>
+        where R: ?Sized + io::Read,
+              F: FnMut(B, u64) -> B
+    {
+        let mut acc = init;
+        while let Some(byte) = next_byte(r)? {
+            acc = f(acc, unsigned(&mut io::Read::chain(&[byte][..], &mut *r))?);
+        }
+        Ok(acc)
+    }
+
+    /// Read signed LEB128 numbers from the given `std::io::Read`able until it
+    /// is exhausted, folding each into an accumulator starting at `init` with
+    /// `f`, and return the final accumulator, or an error if reading failed.
+    ///
+    /// Reaching the end of input between two numbers ends the sequence, while
+    /// reaching it in the middle of a number is an error.
+    pub fn signed_fold<R, B, F>(r: &mut R, init: B, mut f: F) -> Result<B, Error>
+        where R: ?Sized + io::Read,
+              F: FnMut(B, i64) -> B
+    {
+        let mut acc = init;
+        while let Some(byte) = next_byte(r)? {
+            acc = f(acc, signed(&mut io::Read::chain(&[byte][..], &mut *r))?);
+        }
+        Ok(acc)
+    }
+
+    /// Read an unsigned LEB128 number from the given iterator of fallible
+    /// bytes and return it, or an error if the iterator yielded an error or
+    /// ended before the number was complete.
+    pub fn unsigned_from_try_iter<I>(iter: &mut I) -> Result<u64, Error>
+        where I: Iterator<Item = Result<u8, io::Error>>
+    {
+        unsigned(&mut TryIterReader(iter))
+    }
+
+    /// Read a signed LEB128 number from the given iterator of fallible bytes
+    /// and return it, or an error if the iterator yielded an error or ended
+    /// before the number was complete.
+    pub fn signed_from_try_iter<I>(iter: &mut I) -> Result<i64, Error>
+        where I: Iterator<Item = Result<u8, io::Error>>
+    {
+        signed(&mut TryIterReader(iter))
+    }
+
+    /// A reader over an iterator of fallible bytes.
+    struct TryIterReader<'a, I: 'a>(&'a mut I);
+
+    impl<'a, I> io::Read for TryIterReader<'a, I>
+        where I: Iterator<Item = Result<u8, io::Error>>
+    {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if buf.is_empty() {
+                return Ok(0);
+            }
+            match self.0.next() {
+                Some(Ok(byte)) => {
+                    buf[0] = byte;
+                    Ok(1)
+                }
+                Some(Err(e)) => Err(e),
+                None => Ok(0),
+            }
+        }
+    }
+
+    /// A source of bits that need not be aligned to byte boundaries, such as a
+    /// bitstream in which LEB128 numbers follow flags narrower than a byte.
+    pub trait BitRead {
+        /// Read the next eight bits as a byte, whether or not they start on a
+        /// byte boundary. Returns an `UnexpectedEof` error at the end of
+        /// input.
+        fn read_byte(&mut self) -> Result<u8, io::Error>;
+    }
+
+    /// Read an unsigned LEB128 number from the given `BitRead`able and return
+    /// it or an error if reading failed.
+    pub fn unsigned_from_bits<B>(r: &mut B) -> Result<u64, Error>
+        where B: ?Sized + BitRead
+    {
+        unsigned_from_try_iter(&mut iter::from_fn(|| Some(r.read_byte())))
+    }
+
+    /// Read a signed LEB128 number from the given `BitRead`able and return it
+    /// or an error if reading failed.
+    pub fn signed_from_bits<B>(r: &mut B) -> Result<i64, Error>
+        where B: ?Sized + BitRead
+    {
+        signed_from_try_iter(&mut iter::from_fn(|| Some(r.read_byte())))
+    }
+
+    /// Read unsigned LEB128 numbers from the given `std::io::Read`able until
+    /// one equal to `sentinel` is read, and return the numbers preceding it,
+    /// or an error if reading failed, including if the input ends before the
+    /// sentinel.
+    pub fn unsigned_until_sentinel<R>(r: &mut R, sentinel: u64) -> Result<Vec<u64>, Error>
+        where R: ?Sized + io::Read
+    {
+        let mut vals = vec![];
+        loop {
+            let val = unsigned(r)?;
+            if val == sentinel {
+                return Ok(vals);
+            }
+            vals.push(val);
+        }
+    }
+
+    /// Read unsigned LEB128 numbers from the given `std::io::Read`able for as
+    /// long as `pred` returns `true` for them, and return those numbers, or an
+    /// error if reading failed, including if the input ends first.
+    ///
+    /// The first number for which `pred` returns `false` is consumed but not
+    /// returned, just like the sentinel of `unsigned_until_sentinel`.
+    ///
+    /// ```
+    /// let mut readable = &[0x02, 0x04, 0x05, 0x06][..];
+    /// let evens = leb128::read::unsigned_while(&mut readable, |val| val % 2 == 0).unwrap();
+    /// assert_eq!(evens, [2, 4]);
+    /// assert_eq!(readable, [0x06]);
+    /// ```
+    pub fn unsigned_while<R, F>(r: &mut R, mut pred: F) -> Result<Vec<u64>, Error>
+        where R: ?Sized + io::Read,
+              F: FnMut(u64) -> bool
+    {
+        let mut vals = vec![];
+        loop {
+            let val = unsigned(r)?;
+            if !pred(val) {
+                return Ok(vals);
+            }
+            vals.push(val);
+        }
+    }
+
+    /// Read a run-length encoded pair of unsigned LEB128 numbers from the given
+    /// `std::io::Read`able, a repeat count followed by the repeated value, and
+    /// return them as `(count, value)`, or an error if reading failed. If
+    /// `allow_zero_count` is false, a zero count is rejected with an
+    /// `Error::UnexpectedZero` after the whole pair has been read.
+    pub fn rle_unsigned<R>(r: &mut R, allow_zero_count: bool) -> Result<(u64, u64), Error>
+        where R: ?Sized + io::Read
+    {
+        let count = unsigned(r)?;
+        let value = unsigned(r)?;
+        if count == 0 && !allow_zero_count {
+            return Err(Error::UnexpectedZero);
+        }
+        Ok((count, value))
+    }
+
+    /// Read a ragged two-dimensional array of unsigned LEB128 numbers from the
+    /// given `std::io::Read`able, written as the number of rows followed by
+    /// each row's length and numbers, and return its rows, or an error if
+    /// reading failed.
+    ///
+    /// If `max_len` is given, a row count or row length greater than it is an
+    /// `Error::LimitExceeded`, which guards against allocating for absurd
+    /// counts in untrusted input. Regardless, more than
+    /// `DEFAULT_MAX_ELEMENTS` rows and numbers in total is an
+    /// `Error::LimitExceeded`; see `Options::max_elements` to change that.
+    pub fn ragged_unsigned<R>(r: &mut R, max_len: Option<u64>) -> Result<Vec<Vec<u64>>, Error>
+        where R: ?Sized + io::Read
+    {
+        ragged(r, max_len, Some(DEFAULT_MAX_ELEMENTS), unsigned)
+    }
+
+    /// Read a ragged array whose row counts, row lengths and numbers are read
+    /// with `read`, enforcing the row length and total element limits.
+    fn ragged<R, F>(r: &mut R,
+                    max_len: Option<u64>,
+                    max_elements: Option<usize>,
+                    read: F)
+                    -> Result<Vec<Vec<u64>>, Error>
+        where R: ?Sized + io::Read,
+              F: Fn(&mut R) -> Result<u64, Error>
+    {
+        let mut elements = 0u64;
+        let mut read_len = |r: &mut R| -> Result<u64, Error> {
+            let len = read(r)?;
+            elements = elements.saturating_add(len);
+            match (max_len, max_elements) {
+                (Some(max_len), _) if len > max_len => Err(Error::LimitExceeded),
+                (_, Some(max)) if elements > max as u64 => Err(Error::LimitExceeded),
+                _ => Ok(len),
+            }
+        };
+
+        let rows = read_len(r)?;
+        let mut result = vec![];
+        for _ in 0..rows {
+            let len = read_len(r)?;
+            let mut row = vec![];
+            for _ in 0..len {
+                row.push(read(r)?);
+            }
+            result.push(row);
+        }
+        Ok(result)
+    }
+
+    /// Read a sequence of bytes written as the unsigned LEB128 number of bytes
+    /// followed by each byte as its own unsigned LEB128 number from the given
+    /// `std::io::Read`able, and return them, or an `Error::Overflow` if one of
+    /// the numbers is greater than 255, or an error if reading failed.
+    ///
+    /// A count greater than `DEFAULT_MAX_ELEMENTS` is an
+    /// `Error::LimitExceeded`; see `Options::max_elements` to change that.
+    pub fn byte_values<R>(r: &mut R) -> Result<Vec<u8>, Error>
+        where R: ?Sized + io::Read
+    {
+        Options::new().byte_values(r)
+    }
+
+    /// Read a single byte, or return `None` if the reader is exhausted.
+    fn next_byte<R>(r: &mut R) -> Result<Option<u8>, io::Error>
+        where R: ?Sized + io::Read
+    {
+        let mut buf = [0];
+        loop {
+            match r.read(&mut buf) {
+                Ok(0) => return Ok(None),
+                Ok(_) => return Ok(Some(buf[0])),
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Read an unsigned LEB128 number from the given `std::io::Read`able,
+    /// feeding the bytes that encoded it to `hasher`, and return it or an
+    /// error if reading failed. The bytes are fed in a single `Hasher::write`
+    /// call, as `write::unsigned_hashed` does, and only if reading succeeded.
+    pub fn unsigned_hashed<R, H>(r: &mut R, hasher: &mut H) -> Result<u64, Error>
+        where R: ?Sized + io::Read,
+              H: ?Sized + Hasher
+    {
+        hashed(r, hasher, |r| unsigned(r))
+    }
+
+    /// Read a signed LEB128 number from the given `std::io::Read`able,
+    /// feeding the bytes that encoded it to `hasher`, and return it or an
+    /// error if reading failed. The bytes are fed in a single `Hasher::write`
+    /// call, as `write::signed_hashed` does, and only if reading succeeded.
+    pub fn signed_hashed<R, H>(r: &mut R, hasher: &mut H) -> Result<i64, Error>
+        where R: ?Sized + io::Read,
+              H: ?Sized + Hasher
+    {
+        hashed(r, hasher, |r| signed(r))
+    }
+
+    /// Read a number with `read`, buffering the bytes that encoded it, and
+    /// feed them to `hasher` at once. `Hasher` does not promise that split
+    /// writes hash the same as one concatenated write.
+    fn hashed<R, H, T, F>(r: &mut R, hasher: &mut H, read: F) -> Result<T, Error>
+        where R: ?Sized + io::Read,
+              H: ?Sized + Hasher,
+              F: FnOnce(&mut Tee<R, &mut [u8]>) -> Result<T, Error>
+    {
+        // Neither reader consumes more than the longest valid encoding.
+        let mut buf = [0; max_bytes(64)];
+        let (val, len) = {
+            let mut w = &mut buf[..];
+            let val = read(&mut Tee { r, w: &mut w })?;
+            (val, max_bytes(64) - w.len())
+        };
+        hasher.write(&buf[..len]);
+        Ok(val)
+    }
+
+    /// Read an unsigned LEB128 number followed by a checksum byte from the
+    /// given `std::io::Read`able, and return the number, or an
+    /// `Error::ChecksumMismatch` if the checksum is not the XOR of all the
+    /// bytes encoding the number, or an error if reading failed. This is the
+    /// counterpart of `leb128::write::unsigned_checksummed`.
+    pub fn unsigned_checksummed<R>(r: &mut R) -> Result<u64, Error>
+        where R: ?Sized + io::Read
+    {
+        let (val, bytes) = unsigned_with_bytes(r)?;
+        let expected = bytes.iter().fold(0, |sum, byte| sum ^ byte);
+        let mut found = [0];
+        r.read_exact(&mut found)?;
+        if found[0] != expected {
+            return Err(Error::ChecksumMismatch {
+                expected,
+                found: found[0],
+            });
+        }
+        Ok(val)
+    }
+
+    /// Read the unsigned LEB128 number encoded as pairs of hex digits, one
+    /// pair per byte with no separators, that makes up all of the given
+    /// string, and return it. This is the counterpart of
+    /// `leb128::write::unsigned_hex`.
+    ///
+    /// Returns an `InvalidInput` IO error if the string is not made of pairs of
+    /// hex digits, an `InvalidData` IO error if bytes follow the number, or an
+    /// error if the number cannot be read.
+    pub fn unsigned_hex(s: &str) -> Result<u64, Error> {
+        let invalid = || {
+            Error::IoError(io::Error::new(io::ErrorKind::InvalidInput,
+                                          format!("invalid hex string {:?}", s)))
+        };
+        if s.len() % 2 != 0 || !s.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+
+        let mut bytes = vec![];
+        for i in (0..s.len()).step_by(2) {
+            bytes.push(u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| invalid())?);
+        }
+
+        let mut readable = &bytes[..];
+        let val = unsigned(&mut readable)?;
+        if !readable.is_empty() {
+            return Err(Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                     "bytes follow the number")));
+        }
+        Ok(val)
+    }
+
+    /// Statistics about the LEB128 numbers read through an `Instrumented`.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct DecodeStats {
+        /// The number of numbers whose last byte has been read.
+        pub values: u64,
+        /// The number of bytes read.
+        pub bytes: u64,
+        /// The number of numbers that were encoded in a single byte.
+        pub single_byte_values: u64,
+    }
+
+    /// A `std::io::Read`able that collects `DecodeStats` about the LEB128
+    /// numbers read from the wrapped reader, for use with the functions in
+    /// this module.
+    ///
+    /// Numbers are delimited by their continuation bits, so the bytes read
+    /// must all belong to LEB128 numbers for the statistics to be accurate.
+    ///
+    /// ```
+    /// let buf = [0x02, 0x80, 0x01, 0x7f];
+    /// let mut readable = leb128::read::Instrumented::new(&buf[..]);
+    /// while leb128::read::unsigned(&mut readable).is_ok() {}
+    /// let stats = readable.stats();
+    /// assert_eq!((stats.values, stats.bytes, stats.single_byte_values), (3, 4, 2));
+    /// ```
+    #[derive(Debug)]
+    pub struct Instrumented<R> {
+        inner: R,
+        stats: DecodeStats,
+        // The number of bytes read of the current number so far.
+        current: u64,
+    }
+
+    impl<R> Instrumented<R> {
+        /// Wrap the given reader, starting with empty statistics.
+        pub fn new(inner: R) -> Instrumented<R> {
+            Instrumented {
+                inner,
+                stats: DecodeStats::default(),
+                current: 0,
+            }
+        }
+
+        /// Return the statistics collected so far.
+        pub fn stats(&self) -> DecodeStats {
+            self.stats
+        }
+
+        /// Clear the statistics collected so far. A number that has been
+        /// partially read is still counted as a value once it ends.
+        pub fn reset_stats(&mut self) {
+            self.stats = DecodeStats::default();
+        }
+
+        /// Return a reference to the wrapped reader.
+        pub fn get_ref(&self) -> &R {
+            &self.inner
+        }
+
+        /// Unwrap the wrapped reader.
+        pub fn into_inner(self) -> R {
+            self.inner
+        }
+    }
+
+    impl<R: io::Read> io::Read for Instrumented<R> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.inner.read(buf)?;
+            for &byte in &buf[..n] {
+                self.stats.bytes += 1;
+                self.current += 1;
+                if byte & CONTINUATION_BIT == 0 {
+                    self.stats.values += 1;
+                    if self.current == 1 {
+                        self.stats.single_byte_values += 1;
+                    }
+                    self.current = 0;
+                }
+            }
+            Ok(n)
+        }
+    }
+
+    /// A reader that copies every byte read from `r` into `w`.
+    struct Tee<'a, R: ?Sized + 'a, W: ?Sized + 'a> {
+        r: &'a mut R,
+        w: &'a mut W,
+    }
+
+    impl<'a, R, W> io::Read for Tee<'a, R, W>
+        where R: ?Sized + io::Read,
+              W: ?Sized + io::Write
+    {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = self.r.read(buf)?;
+            self.w.write_all(&buf[..n])?;
+            Ok(n)
+        }
+    }
+}
+
+/// A module for writing integers encoded as LEB128.
+pub mod write {
+    use super::{CONTINUATION_BIT, low_bits_of_u64, max_bytes};
+    use std::convert::TryFrom;
+    use std::fmt;
+    use std::hash::Hasher;
+    use std::io;
+    use std::net::SocketAddr;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    #[cfg(feature = "bigint")]
+    use num_bigint::BigUint;
+
+    /// An enumeration of the possible errors that can occur when writing a
+    /// number with a range checked writer.
+    #[derive(Debug)]
+    pub enum Error {
+        /// There was an underlying IO error.
+        IoError(io::Error),
+        /// The number being written is outside of the allowed range.
+        OutOfRange,
+        /// The buffer being written into is too small to hold the encoding.
+        BufferTooSmall,
+    }
+
+    impl From<io::Error> for Error {
+        fn from(e: io::Error) -> Self {
+            Error::IoError(e)
+        }
+    }
+
+    impl From<Error> for io::Error {
+        fn from(e: Error) -> Self {
+            match e {
+                Error::IoError(e) => e,
+                Error::OutOfRange => io::Error::new(io::ErrorKind::InvalidInput, e.to_string()),
+                Error::BufferTooSmall => io::Error::new(io::ErrorKind::WriteZero, e.to_string()),
+            }
+        }
+    }
+
+    impl fmt::Display for Error {
+        fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+            match *self {
+                Error::IoError(ref e) => e.fmt(f),
+                Error::OutOfRange => {
+                    write!(f, "The number being written is outside of the allowed range")
+                }
+                Error::BufferTooSmall => {
+                    write!(f, "The buffer is too small to hold the encoded number")
+                }
+            }
+        }
+    }
+
+    impl ::std::error::Error for Error {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            match *self {
+                Error::IoError(ref e) => Some(e),
+                _ => None,
+            }
+        }
+    }
+
+    /// Return the number of bytes that `unsigned` writes for the given number.
+    pub const fn unsigned_len(val: u64) -> usize {
+        let bits = 64 - val.leading_zeros();
+        if bits == 0 { 1 } else { max_bytes(bits) }
+    }
+
+    /// Return the number of bytes that `signed` writes for the given number.
+    pub const fn signed_len(val: i64) -> usize {
+        // The significant bits, plus one for the sign.
+        let bits = if val < 0 {
+            64 - (!val).leading_zeros() + 1
+        } else {
+            64 - val.leading_zeros() + 1
+        };
+        max_bytes(bits)
+    }
+
+    /// Return the total number of bytes that `unsigned` writes for all of the
+    /// given numbers.
+    pub fn unsigned_total_len(vals: &[u64]) -> usize {
+        vals.iter().map(|&val| unsigned_len(val)).sum()
+    }
+
+    /// Return the total number of bytes that `signed` writes for all of the
+    /// given numbers.
+    pub fn signed_total_len(vals: &[i64]) -> usize {
+        vals.iter().map(|&val| signed_len(val)).sum()
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able. Returns the number of bytes written to `w`, or an
+    /// error if writing failed.
+    pub fn unsigned<W>(w: &mut W, mut val: u64) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let mut bytes_written = 0;
+        loop {
+            let mut byte = low_bits_of_u64(val);
+            val >>= 7;
+            if val != 0 {
+                // More bytes to come, so set the continuation bit.
+                byte |= CONTINUATION_BIT;
+            }
+
+            let buf = [byte];
+            w.write_all(&buf)?;
+            bytes_written += 1;
+
+            if val == 0 {
+                return Ok(bytes_written);
+            }
+        }
+    }
+
+    /// Write the given signed number using the LEB128 encoding to the given
+    /// `std::io::Write`able. Returns the number of bytes written to `w`, or an
+    /// error if writing failed.
+    pub fn signed<W>(w: &mut W, mut val: i64) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let mut bytes_written = 0;
+        loop {
+            let mut byte = val as u8;
+            // Keep the sign bit for testing
+            val >>= 6;
+            let done = val == 0 || val == -1;
+            if done {
+                byte &= !CONTINUATION_BIT;
+            } else {
+                // Remove the sign bit
+                val >>= 1;
+                // More bytes to come, so set the continuation bit.
+                byte |= CONTINUATION_BIT;
+            }
+
+            let buf = [byte];
+            w.write_all(&buf)?;
+            bytes_written += 1;
+
+            if done {
+                return Ok(bytes_written);
+            }
+        }
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write` trait object. Unlike `unsigned`, this is not generic,
+    /// so it can be stored as a `fn` pointer.
+    pub fn unsigned_dyn(w: &mut dyn io::Write, val: u64) -> Result<usize, io::Error> {
+        unsigned(w, val)
+    }
+
+    /// Write the given signed number using the LEB128 encoding to the given
+    /// `std::io::Write` trait object. Unlike `signed`, this is not generic, so
+    /// it can be stored as a `fn` pointer.
+    pub fn signed_dyn(w: &mut dyn io::Write, val: i64) -> Result<usize, io::Error> {
+        signed(w, val)
+    }
+
+    /// Write the given signed number using the LEB128 encoding into the front
+    /// of `buf`. Returns the subslice of `buf` that was written, or an
+    /// `Error::BufferTooSmall` without writing anything if `buf` is shorter
+    /// than `signed_len(val)`.
+    pub fn signed_into(buf: &mut [u8], val: i64) -> Result<&mut [u8], Error> {
+        let len = signed_len(val);
+        if buf.len() < len {
+            return Err(Error::BufferTooSmall);
+        }
+
+        let written = &mut buf[..len];
+        signed(&mut &mut written[..], val)?;
+        Ok(written)
+    }
+
+    /// Write a protobuf-style tag followed by the given unsigned value to the
+    /// given `std::io::Write`able. The tag `(field << 3) | wire_type` and the
+    /// value are both encoded as unsigned LEB128. Returns the total number of
+    /// bytes written to `w`, or an error if `wire_type` does not fit in three
+    /// bits or writing failed.
+    pub fn tagged_unsigned<W>(w: &mut W,
+                              field: u32,
+                              wire_type: u8,
+                              val: u64)
+                              -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        if wire_type >= 8 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "wire type must be less than 8"));
+        }
+
+        let tag = (field as u64) << 3 | wire_type as u64;
+        let bytes_written = unsigned(w, tag)?;
+        Ok(bytes_written + unsigned(w, val)?)
+    }
+
+    /// Write the given signed number using the zigzag encoding followed by the
+    /// unsigned LEB128 encoding to the given `std::io::Write`able. Zigzag
+    /// encoding maps numbers of small magnitude, whether positive or negative,
+    /// to small unsigned numbers. Returns the number of bytes written to `w`,
+    /// or an error if writing failed.
+    pub fn zigzag<W>(w: &mut W, val: i64) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        unsigned(w, ((val << 1) ^ (val >> 63)) as u64)
+    }
+
+    /// Write the given `i32` the way .NET's `BinaryWriter.Write7BitEncodedInt`
+    /// does, as the unsigned LEB128 encoding of the number reinterpreted as a
+    /// `u32`, to the given `std::io::Write`able. Negative numbers always take
+    /// five bytes. Returns the number of bytes written to `w`, or an error if
+    /// writing failed.
+    pub fn net_7bit_i32<W>(w: &mut W, val: i32) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        unsigned(w, val as u32 as u64)
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able, if it is at most `max`. Returns the number of
+    /// bytes written to `w`, or an `Error::OutOfRange` without writing anything
+    /// if `val` is greater than `max`, or an error if writing failed.
+    pub fn unsigned_in_range<W>(w: &mut W, val: u64, max: u64) -> Result<usize, Error>
+        where W: ?Sized + io::Write
+    {
+        if val > max {
+            return Err(Error::OutOfRange);
+        }
+        Ok(unsigned(w, val)?)
+    }
+
+    /// Write the given signed number using the LEB128 encoding to the given
+    /// `std::io::Write`able, if it is between `min` and `max` inclusive.
+    /// Returns the number of bytes written to `w`, or an `Error::OutOfRange`
+    /// without writing anything if `val` is out of that range, or an error if
+    /// writing failed.
+    pub fn signed_in_range<W>(w: &mut W, val: i64, min: i64, max: i64) -> Result<usize, Error>
+        where W: ?Sized + io::Write
+    {
+        if val < min || val > max {
+            return Err(Error::OutOfRange);
+        }
+        Ok(signed(w, val)?)
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able, if it fits in 24 bits. Returns the number of
+    /// bytes written to `w`, or an `Error::OutOfRange` without writing
+    /// anything if it does not, or an error if writing failed.
+    pub fn unsigned_u24<W>(w: &mut W, val: u32) -> Result<usize, Error>
+        where W: ?Sized + io::Write
+    {
+        unsigned_in_range(w, val as u64, (1 << 24) - 1)
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able, if it fits in 48 bits. Returns the number of
+    /// bytes written to `w`, or an `Error::OutOfRange` without writing
+    /// anything if it does not, or an error if writing failed.
+    pub fn unsigned_u48<W>(w: &mut W, val: u64) -> Result<usize, Error>
+        where W: ?Sized + io::Write
+    {
+        unsigned_in_range(w, val, (1 << 48) - 1)
+    }
+
+    /// Write the given signed number as a sign byte, `0` for positive or `1`
+    /// for negative, followed by the unsigned LEB128 encoding of its magnitude
+    /// to the given `std::io::Write`able. Returns the number of bytes written
+    /// to `w`, or an error if writing failed.
+    pub fn sign_magnitude<W>(w: &mut W, val: i64) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        w.write_all(&[(val < 0) as u8])?;
+        Ok(1 + unsigned(w, val.unsigned_abs())?)
+    }
+
+    /// Write the given number to the given `std::io::Write`able as a tag byte
+    /// followed by its LEB128 encoding: tag `0` and the unsigned encoding if
+    /// it is not negative, or tag `1` and the signed encoding if it is.
+    /// Returns the number of bytes written to `w`, or an `Error::OutOfRange`
+    /// without writing anything if `val` is below `i64::MIN` or above
+    /// `u64::MAX`, or an error if writing failed.
+    pub fn tagged_integer<W>(w: &mut W, val: i128) -> Result<usize, Error>
+        where W: ?Sized + io::Write
+    {
+        if val < 0 {
+            let val = i64::try_from(val).map_err(|_| Error::OutOfRange)?;
+            w.write_all(&[1])?;
+            Ok(1 + signed(w, val)?)
+        } else {
+            let val = u64::try_from(val).map_err(|_| Error::OutOfRange)?;
+            w.write_all(&[0])?;
+            Ok(1 + unsigned(w, val)?)
+        }
+    }
+
+    /// Write the unsigned 128-bit number made of the given `low` and `high`
+    /// 64-bit halves using the LEB128 encoding to the given `std::io::Write`able.
+    /// Only 64-bit arithmetic is used, for targets where `u128` is unavailable
+    /// or slow. Returns the number of bytes written to `w`, or an error if
+    /// writing failed.
+    pub fn unsigned_u128_halves<W>(w: &mut W,
+                                   mut low: u64,
+                                   mut high: u64)
+                                   -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let mut bytes_written = 0;
+        loop {
+            let mut byte = low_bits_of_u64(low);
+            // Shift the 128-bit number right by 7, carrying from the high half.
+            low = (low >> 7) | (high << 57);
+            high >>= 7;
+            if low != 0 || high != 0 {
+                byte |= CONTINUATION_BIT;
+            }
+
+            let buf = [byte];
+            w.write_all(&buf)?;
+            bytes_written += 1;
+
+            if low == 0 && high == 0 {
+                return Ok(bytes_written);
+            }
+        }
+    }
+
+    /// Write the given unsigned number of any size using the LEB128 encoding
+    /// to the given `std::io::Write`able. Returns the number of bytes written
+    /// to `w`, or an error if writing failed.
+    #[cfg(feature = "bigint")]
+    pub fn big_unsigned<W>(w: &mut W, val: &BigUint) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        // Each base 128 digit, least significant first, is a byte.
+        let mut bytes = val.to_radix_le(128);
+        let last = bytes.len() - 1;
+        for byte in &mut bytes[..last] {
+            *byte |= CONTINUATION_BIT;
+        }
+        w.write_all(&bytes)?;
+        Ok(bytes.len())
+    }
+
+    /// Write the given `Duration` as the unsigned LEB128 numbers of its whole
+    /// seconds and of its additional nanoseconds to the given
+    /// `std::io::Write`able. Returns the number of bytes written to `w`, or an
+    /// error if writing failed.
+    pub fn duration<W>(w: &mut W, d: Duration) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        Ok(unsigned(w, d.as_secs())? + unsigned(w, d.subsec_nanos() as u64)?)
+    }
+
+    /// Write the given `SystemTime` as the signed LEB128 number of whole
+    /// seconds since `UNIX_EPOCH`, rounded down so that times before the epoch
+    /// are negative, and the unsigned LEB128 number of additional nanoseconds
+    /// to the given `std::io::Write`able. Returns the number of bytes written
+    /// to `w`, or an error if the time is too far from the epoch for its
+    /// seconds to fit in an `i64` or writing failed.
+    pub fn system_time<W>(w: &mut W, t: SystemTime) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let (d, after_epoch) = match t.duration_since(UNIX_EPOCH) {
+            Ok(d) => (d, true),
+            Err(e) => (e.duration(), false),
+        };
+        if d.as_secs() > i64::MAX as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "time is too far from the epoch"));
+        }
+
+        let secs = d.as_secs() as i64;
+        let (secs, nanos) = match (after_epoch, d.subsec_nanos()) {
+            (true, nanos) => (secs, nanos),
+            (false, 0) => (-secs, 0),
+            (false, nanos) => (-secs - 1, 1_000_000_000 - nanos),
+        };
+        Ok(signed(w, secs)? + unsigned(w, nanos as u64)?)
+    }
+
+    /// Write the given `SocketAddr` as a version byte, `4` or `6`, the octets
+    /// of the IP address, and the unsigned LEB128 number of the port to the
+    /// given `std::io::Write`able. The flow information and scope of an IPv6
+    /// address are not written. Returns the number of bytes written to `w`, or
+    /// an error if writing failed.
+    pub fn socket_addr<W>(w: &mut W, addr: SocketAddr) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let ip_len = match addr {
+            SocketAddr::V4(addr) => {
+                w.write_all(&[4])?;
+                w.write_all(&addr.ip().octets())?;
+                4
+            }
+            SocketAddr::V6(addr) => {
+                w.write_all(&[6])?;
+                w.write_all(&addr.ip().octets())?;
+                16
+            }
+        };
+        Ok(1 + ip_len + unsigned(w, addr.port() as u64)?)
+    }
+
+    /// Write the given unsigned numbers using the LEB128 encoding to the given
+    /// `std::io::Write`able, followed by `sentinel` to terminate the list.
+    /// Returns the number of bytes written to `w`, or an error if one of the
+    /// numbers is equal to the sentinel or writing failed.
+    pub fn unsigned_with_sentinel<W>(w: &mut W,
+                                     vals: &[u64],
+                                     sentinel: u64)
+                                     -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        if vals.contains(&sentinel) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "list contains the sentinel value"));
+        }
+
+        let mut bytes_written = 0;
+        for &val in vals {
+            bytes_written += unsigned(w, val)?;
+        }
+        Ok(bytes_written + unsigned(w, sentinel)?)
+    }
+
+    /// Write a run-length encoded pair of unsigned numbers using the LEB128
+    /// encoding to the given `std::io::Write`able, the repeat count followed by
+    /// the repeated value. Returns the number of bytes written to `w`, or an
+    /// error if `count` is zero while `allow_zero_count` is false or writing
+    /// failed.
+    pub fn rle_unsigned<W>(w: &mut W,
+                           count: u64,
+                           value: u64,
+                           allow_zero_count: bool)
+                           -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        if count == 0 && !allow_zero_count {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "repeat count is zero"));
+        }
+
+        Ok(unsigned(w, count)? + unsigned(w, value)?)
+    }
+
+    /// Write the given ragged two-dimensional array of unsigned numbers using
+    /// the LEB128 encoding to the given `std::io::Write`able, as the number of
+    /// rows followed by each row's length and numbers. Returns the number of
+    /// bytes written to `w`, or an error if writing failed.
+    pub fn ragged_unsigned<W>(w: &mut W, rows: &[Vec<u64>]) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let mut bytes_written = unsigned(w, rows.len() as u64)?;
+        for row in rows {
+            bytes_written += unsigned(w, row.len() as u64)?;
+            for &val in row {
+                bytes_written += unsigned(w, val)?;
+            }
+        }
+        Ok(bytes_written)
+    }
+
+    /// Write the given bytes as the unsigned LEB128 number of bytes followed by
+    /// each byte as its own unsigned LEB128 number to the given
+    /// `std::io::Write`able. Unlike `length_prefixed`, bytes of 128 or more
+    /// take two bytes each. Returns the number of bytes written to `w`, or an
+    /// error if writing failed.
+    pub fn byte_values<W>(w: &mut W, bytes: &[u8]) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let mut bytes_written = unsigned(w, bytes.len() as u64)?;
+        for &byte in bytes {
+            bytes_written += unsigned(w, byte as u64)?;
+        }
+        Ok(bytes_written)
+    }
+
+    /// Write the length of the given bytes using the unsigned LEB128 encoding
+    /// to the given `std::io::Write`able, followed by the bytes themselves.
+    /// Returns the total number of bytes written to `w`, or an error if
+    /// writing failed.
+    pub fn length_prefixed<W>(w: &mut W, bytes: &[u8]) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let prefix_len = unsigned(w, bytes.len() as u64)?;
+        w.write_all(bytes)?;
+        Ok(prefix_len + bytes.len())
+    }
+
+    /// Write `len` using the unsigned LEB128 encoding to the given
+    /// `std::io::Write`able, followed by `len` bytes copied from `r` through a
+    /// fixed-size buffer, so that the payload never has to be held in memory.
+    /// Returns the total number of bytes written to `w`, or an error if `r`
+    /// ends before `len` bytes were copied or reading or writing failed.
+    pub fn length_prefixed_from_reader<W, R>(w: &mut W,
+                                             r: &mut R,
+                                             len: u64)
+                                             -> Result<u64, io::Error>
+        where W: ?Sized + io::Write,
+              R: ?Sized + io::Read
+    {
+        let prefix_len = unsigned(w, len)? as u64;
+
+        let mut buf = [0; 8192];
+        let mut remaining = len;
+        while remaining > 0 {
+            let chunk_len = if remaining < buf.len() as u64 {
+                remaining as usize
+            } else {
+                buf.len()
+            };
+            let chunk = &mut buf[..chunk_len];
+            r.read_exact(chunk)?;
+            w.write_all(chunk)?;
+            remaining -= chunk_len as u64;
+        }
+
+        Ok(prefix_len + len)
+    }
+
+    /// Write the bytes produced by `body` to the given `std::io::Write`able,
+    /// followed by their length using the unsigned LEB128 encoding, so that
+    /// the body can be located from the end with `read::from_length_footer`.
+    /// The body is buffered in memory first. Returns the total number of bytes
+    /// written to `w`, or an error if `body` or writing failed.
+    pub fn with_length_footer<W, F>(w: &mut W, body: F) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write,
+              F: FnOnce(&mut Vec<u8>) -> io::Result<()>
+    {
+        let mut buf = vec![];
+        body(&mut buf)?;
+        w.write_all(&buf)?;
+        Ok(buf.len() + unsigned(w, buf.len() as u64)?)
+    }
+
+    /// Write the Unicode scalar value of the given `char` using the unsigned
+    /// LEB128 encoding to the given `std::io::Write`able. Returns the number of
+    /// bytes written to `w`, or an error if writing failed.
+    pub fn char<W>(w: &mut W, c: char) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        unsigned(w, c as u64)
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able, padded with redundant zero bytes if necessary so
+    /// that at least `min_bytes` bytes are written. Numbers whose encoding is
+    /// already long enough are written as is. Returns the number of bytes
+    /// written to `w`, or an error if `min_bytes` is greater than 10 or
+    /// writing failed.
+    pub fn unsigned_min_bytes<W>(w: &mut W, val: u64, min_bytes: usize) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        if min_bytes > MAX_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "minimum width must be at most 10 bytes"));
+        }
+
+        let mut buf = [0; MAX_LEN];
+        let len = unsigned(&mut &mut buf[..], val)?;
+        if len >= min_bytes {
+            w.write_all(&buf[..len])?;
+            return Ok(len);
+        }
+
+        for byte in &mut buf[len - 1..min_bytes - 1] {
+            *byte |= CONTINUATION_BIT;
+        }
+        w.write_all(&buf[..min_bytes])?;
+        Ok(min_bytes)
+    }
+
+    /// Return the number of padding bytes that must be added to an encoding
+    /// of `encoded_len` bytes so that its length is a multiple of `align`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is zero.
+    pub fn align_padding(encoded_len: usize, align: usize) -> usize {
+        assert!(align > 0, "alignment must be non-zero");
+        (align - encoded_len % align) % align
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able, padded with redundant zero bytes so that the
+    /// number of bytes written is a multiple of `align`. Returns the number of
+    /// bytes written to `w`, or an error if the padded encoding would be longer
+    /// than 10 bytes or writing failed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `align` is zero.
+    pub fn unsigned_aligned<W>(w: &mut W, val: u64, align: usize) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let len = unsigned_len(val);
+        unsigned_min_bytes(w, val, len + align_padding(len, align))
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able, padded with redundant zero bytes up to the next
+    /// width of 1, 2, 4 or 8 bytes. Numbers that need more than 8 bytes are
+    /// written at their natural width. Returns the number of bytes written to
+    /// `w`, or an error if writing failed.
+    pub fn unsigned_pow2_padded<W>(w: &mut W, val: u64) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let len = unsigned_len(val);
+        let padded = if len <= 8 { len.next_power_of_two() } else { len };
+        unsigned_min_bytes(w, val, padded)
+    }
+
+    /// Write the given signed number using the LEB128 encoding to the given
+    /// `std::io::Write`able, padded with sign extension bytes so that exactly
+    /// `bytes` bytes are written. Returns the number of bytes written to `w`,
+    /// or an error if `bytes` is not between 1 and 10, `val` does not fit in
+    /// `bytes * 7` bits, or writing failed.
+    pub fn signed_fixed_width<W>(w: &mut W, val: i64, bytes: usize) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        if bytes == 0 || bytes > MAX_LEN {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "fixed width must be between 1 and 10 bytes"));
+        }
+
+        let bits = bytes * 7;
+        if bits < 64 && (val < -(1 << (bits - 1)) || val >= 1 << (bits - 1)) {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "number does not fit in the fixed width"));
+        }
+
+        let mut buf = [0; MAX_LEN];
+        for (i, byte) in buf[..bytes].iter_mut().enumerate() {
+            let shift = if i * 7 < 63 { i * 7 } else { 63 };
+            *byte = (val >> shift) as u8 & !CONTINUATION_BIT;
+            if i + 1 < bytes {
+                *byte |= CONTINUATION_BIT;
+            }
+        }
+
+        w.write_all(&buf[..bytes])?;
+        Ok(bytes)
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able and also feed the encoded bytes to `hasher`.
+    /// Returns the number of bytes written to `w`, or an error if writing
+    /// failed. The hasher is only updated if writing succeeded.
+    pub fn unsigned_hashed<W, H>(w: &mut W, val: u64, hasher: &mut H) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write,
+              H: ?Sized + Hasher
+    {
+        let mut buf = [0; MAX_LEN];
+        let len = unsigned(&mut &mut buf[..], val)?;
+        w.write_all(&buf[..len])?;
+        hasher.write(&buf[..len]);
+        Ok(len)
+    }
+
+    /// Write the given signed number using the LEB128 encoding to the given
+    /// `std::io::Write`able and also feed the encoded bytes to `hasher`.
+    /// Returns the number of bytes written to `w`, or an error if writing
+    /// failed. The hasher is only updated if writing succeeded.
+    pub fn signed_hashed<W, H>(w: &mut W, val: i64, hasher: &mut H) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write,
+              H: ?Sized + Hasher
+    {
+        let mut buf = [0; MAX_LEN];
+        let len = signed(&mut &mut buf[..], val)?;
+        w.write_all(&buf[..len])?;
+        hasher.write(&buf[..len]);
+        Ok(len)
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::fmt::Write`able as pairs of lowercase hex digits, one pair per
+    /// byte, with no separators. Only `core` is needed, so this is also
+    /// usable with formatters in text formats. Returns an error if writing
+    /// failed.
+    ///
+    /// ```
+    /// let mut s = String::new();
+    /// leb128::write::unsigned_hex(&mut s, 624485).unwrap();
+    /// assert_eq!(s, "e58e26");
+    /// ```
+    pub fn unsigned_hex<W>(w: &mut W, mut val: u64) -> fmt::Result
+        where W: ?Sized + fmt::Write
+    {
+        loop {
+            let mut byte = low_bits_of_u64(val);
+            val >>= 7;
+            if val != 0 {
+                byte |= CONTINUATION_BIT;
+            }
+
+            write!(w, "{:02x}", byte)?;
+
+            if val == 0 {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Write the given unsigned number using the LEB128 encoding to the given
+    /// `std::io::Write`able, followed by a checksum byte that is the XOR of all
+    /// the bytes encoding the number. Returns the number of bytes written to
+    /// `w`, including the checksum, or an error if writing failed.
+    pub fn unsigned_checksummed<W>(w: &mut W, val: u64) -> Result<usize, io::Error>
+        where W: ?Sized + io::Write
+    {
+        let mut buf = [0; MAX_LEN + 1];
+        let len = unsigned(&mut &mut buf[..], val)?;
+        buf[len] = buf[..len].iter().fold(0, |sum, byte| sum ^ byte);
+        w.write_all(&buf[..len + 1])?;
+        Ok(len + 1)
+    }
+
+    /// Return a `std::io::Read`able that yields the unsigned LEB128 encoding
+    /// of the given number, so that it can be copied into any sink or
+    /// combined with other readers without an intermediate allocation.
+    ///
+    /// ```
+    /// use std::io;
+    ///
+    /// let mut buf = vec![];
+    /// io::copy(&mut leb128::write::unsigned_reader(128), &mut buf).unwrap();
+    /// assert_eq!(buf, [0x80, 0x01]);
+    /// ```
+    pub fn unsigned_reader(val: u64) -> impl io::Read {
+        let mut reader = EncodedReader {
+            buf: [0; MAX_LEN],
+            pos: 0,
+            len: 0,
+        };
+        reader.len = unsigned(&mut &mut reader.buf[..], val).expect("buffer is large enough");
+        reader
+    }
+
+    /// Return a `std::io::Read`able that yields the signed LEB128 encoding of
+    /// the given number. See `unsigned_reader`.
+    pub fn signed_reader(val: i64) -> impl io::Read {
+        let mut reader = EncodedReader {
+            buf: [0; MAX_LEN],
+            pos: 0,
+            len: 0,
+        };
+        reader.len = signed(&mut &mut reader.buf[..], val).expect("buffer is large enough");
+        reader
+    }
+
+    /// An incremental unsigned LEB128 encoder that yields one byte at a time,
+    /// so that a number can be split across fixed-size frames and resumed
+    /// later.
+    ///
+    /// ```
+    /// let mut encoder = leb128::write::UnsignedEncoder::new(624485);
+    /// assert_eq!(encoder.next_byte(), Some(0xe5));
+    /// // ... start a new frame ...
+    /// assert_eq!(encoder.next_byte(), Some(0x8e));
+    /// assert_eq!(encoder.next_byte(), Some(0x26));
+    /// assert_eq!(encoder.next_byte(), None);
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct UnsignedEncoder {
+        val: u64,
+        done: bool,
+    }
+
+    impl UnsignedEncoder {
+        /// Create an encoder for the given number.
+        pub fn new(val: u64) -> Self {
+            UnsignedEncoder { val, done: false }
+        }
+
+        /// Return the next encoded byte, or `None` once every byte has been
+        /// returned.
+        pub fn next_byte(&mut self) -> Option<u8> {
+            if self.done {
+                return None;
+            }
+
+            let mut byte = low_bits_of_u64(self.val);
+            self.val >>= 7;
+            if self.val != 0 {
+                // More bytes to come, so set the continuation bit.
+                byte |= CONTINUATION_BIT;
+            } else {
+                self.done = true;
+            }
+            Some(byte)
+        }
+    }
+
+    impl Iterator for UnsignedEncoder {
+        type Item = u8;
+
+        fn next(&mut self) -> Option<u8> {
+            self.next_byte()
+        }
+    }
+
+    /// A `std::io::Write`able that forwards to an inner writer and keeps a
+    /// running total of the bytes written through it, for example to compute
+    /// the size of a section.
+    ///
+    /// ```
+    /// use leb128::write::{self, CountingWriter};
+    ///
+    /// let mut w = CountingWriter::new(vec![]);
+    /// write::unsigned(&mut w, 300).unwrap();
+    /// write::signed(&mut w, -1).unwrap();
+    /// assert_eq!(w.bytes_written(), 3);
+    /// ```
+    #[derive(Debug)]
+    pub struct CountingWriter<W> {
+        inner: W,
+        bytes_written: u64,
+    }
+
+    impl<W> CountingWriter<W> {
+        /// Wrap the given writer, starting the count at zero.
+        pub fn new(inner: W) -> Self {
+            CountingWriter {
+                inner,
+                bytes_written: 0,
+            }
+        }
+
+        /// Return the number of bytes written since creation or the last
+        /// `reset`.
+        pub fn bytes_written(&self) -> u64 {
+            self.bytes_written
+        }
+
+        /// Reset the count to zero.
+        pub fn reset(&mut self) {
+            self.bytes_written = 0;
+        }
+
+        /// Return a reference to the inner writer.
+        pub fn get_ref(&self) -> &W {
+            &self.inner
+        }
+
+        /// Return a mutable reference to the inner writer. Bytes written
+        /// directly to it are not counted.
+        pub fn get_mut(&mut self) -> &mut W {
+            &mut self.inner
+        }
+
+        /// Unwrap and return the inner writer.
+        pub fn into_inner(self) -> W {
+            self.inner
+        }
+    }
+
+    impl<W: io::Write> io::Write for CountingWriter<W> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            let n = self.inner.write(buf)?;
+            self.bytes_written += n as u64;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    /// The maximum length of a 64-bit number encoded as LEB128.
+    const MAX_LEN: usize = max_bytes(64);
+
+    /// A reader over a single encoded number.
+    struct EncodedReader {
+        buf: [u8; MAX_LEN],
+        pos: usize,
+        len: usize,
+    }
+
+    impl io::Read for EncodedReader {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = (&self.buf[self.pos..self.len]).read(buf)?;
+            self.pos += n;
+            Ok(n)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_low_bits_of_byte() {
+        for i in 0..127 {
+            assert_eq!(i, low_bits_of_byte(i));
+            assert_eq!(i, low_bits_of_byte(i | CONTINUATION_BIT));
+        }
+    }
+
+    #[test]
+    fn test_low_bits_of_u64() {
+        for i in 0u64..127 {
+            assert_eq!(i as u8, low_bits_of_u64(1 << 16 | i));
+            assert_eq!(i as u8,
+                       low_bits_of_u64(i << 16 | i | (CONTINUATION_BIT as u64)));
+        }
+    }
+
+    #[test]
+    fn test_max_bytes() {
+        assert_eq!(max_bytes(0), 0);
+        assert_eq!(max_bytes(1), 1);
+        assert_eq!(max_bytes(7), 1);
+        assert_eq!(max_bytes(8), 2);
+        assert_eq!(max_bytes(14), 2);
+        assert_eq!(max_bytes(32), 5);
+        assert_eq!(max_bytes(63), 9);
+        assert_eq!(max_bytes(64), 10);
+    }
+
+    #[test]
+    fn test_max_value_in_bytes() {
+        assert_eq!(max_value_in_bytes(0), 0);
+        assert_eq!(max_value_in_bytes(1), 127);
+        assert_eq!(max_value_in_bytes(2), 16383);
+        assert_eq!(max_value_in_bytes(9), (1 << 63) - 1);
+        assert_eq!(max_value_in_bytes(10), (1 << 70) - 1);
+        assert_eq!(max_value_in_bytes(18), (1 << 126) - 1);
+        assert_eq!(max_value_in_bytes(19), u128::MAX);
+        assert_eq!(max_value_in_bytes(usize::MAX), u128::MAX);
+
+        for bytes in 1..10 {
+            let mut buf = vec![];
+            let max = max_value_in_bytes(bytes) as u64;
+            assert_eq!(write::unsigned(&mut buf, max).expect("Should write number"),
+                       bytes);
+            assert_eq!(write::unsigned(&mut buf, max + 1).expect("Should write number"),
+                       bytes + 1);
+        }
+    }
+
+    // Examples from the DWARF 4 standard, section 7.6, figure 22.
+    #[test]
+    fn test_read_unsigned() {
+        let buf = [2u8];
+        let mut readable = &buf[..];
+        assert_eq!(2,
+                   read::unsigned(&mut readable).expect("Should read number"));
+
+        let buf = [127u8];
+        let mut readable = &buf[..];
+        assert_eq!(127,
+                   read::unsigned(&mut readable).expect("Should read number"));
+
+        let buf = [CONTINUATION_BIT, 1];
+        let mut readable = &buf[..];
+        assert_eq!(128,
+                   read::unsigned(&mut readable).expect("Should read number"));
+
+        let buf = [1u8 | CONTINUATION_BIT, 1];
+        let mut readable = &buf[..];
+        assert_eq!(129,
+                   read::unsigned(&mut readable).expect("Should read number"));
+
+        let buf = [2u8 | CONTINUATION_BIT, 1];
+        let mut readable = &buf[..];
+        assert_eq!(130,
+                   read::unsigned(&mut readable).expect("Should read number"));
+
+        let buf = [57u8 | CONTINUATION_BIT, 100];
+        let mut readable = &buf[..];
+        assert_eq!(12857,
+                   read::unsigned(&mut readable).expect("Should read number"));
+    }
+
+    // Examples from the DWARF 4 standard, section 7.6, figure 23.
+    #[test]
+    fn test_read_signed() {
+        let buf = [2u8];
+        let mut readable = &buf[..];
+        assert_eq!(2, read::signed(&mut readable).expect("Should read number"));
+
+        let buf = [0x7eu8];
+        let mut readable = &buf[..];
+        assert_eq!(-2, read::signed(&mut readable).expect("Should read number"));
+
+        let buf = [127u8 | CONTINUATION_BIT, 0];
+        let mut readable = &buf[..];
+        assert_eq!(127,
+                   read::signed(&mut readable).expect("Should read number"));
+
+        let buf = [1u8 | CONTINUATION_BIT, 0x7f];
+        let mut readable = &buf[..];
+        assert_eq!(-127,
+                   read::signed(&mut readable).expect("Should read number"));
+
+        let buf = [CONTINUATION_BIT, 1];
+        let mut readable = &buf[..];
+        assert_eq!(128,
+                   read::signed(&mut readable).expect("Should read number"));
+
+        let buf = [CONTINUATION_BIT, 0x7f];
+        let mut readable = &buf[..];
+        assert_eq!(-128,
+                   read::signed(&mut readable).expect("Should read number"));
+
+        let buf = [1u8 | CONTINUATION_BIT, 1];
+        let mut readable = &buf[..];
+        assert_eq!(129,
+                   read::signed(&mut readable).expect("Should read number"));
+
+        let buf = [0x7fu8 | CONTINUATION_BIT, 0x7e];
+        let mut readable = &buf[..];
+        assert_eq!(-129,
+                   read::signed(&mut readable).expect("Should read number"));
+    }
+
+    #[test]
+    fn test_read_single_byte() {
+        for byte in 0..CONTINUATION_BIT {
+            let buf = [byte];
+            assert_eq!(read::unsigned(&mut &buf[..]).expect("Should read number"),
+                       byte as u64);
+
+            let expected = if byte & SIGN_BIT == 0 {
+                byte as i64
+            } else {
+                byte as i64 - 128
+            };
+            assert_eq!(read::signed(&mut &buf[..]).expect("Should read number"),
+                       expected);
+        }
+    }
+
+    #[test]
+    fn test_read_signed_63_bits() {
+        let buf = [CONTINUATION_BIT,
+                   CONTINUATION_BIT,
                    CONTINUATION_BIT,
                    CONTINUATION_BIT,
                    CONTINUATION_BIT,
@@ -344,169 +3422,2446 @@ mod tests {
                    CONTINUATION_BIT,
                    0x40];
         let mut readable = &buf[..];
-        assert_eq!(-0x4000000000000000,
-                   read::signed(&mut readable).expect("Should read number"));
+        assert_eq!(-0x4000000000000000,
+                   read::signed(&mut readable).expect("Should read number"));
+    }
+
+    #[test]
+    fn test_read_unsigned_not_enough_data() {
+        let buf = [CONTINUATION_BIT];
+        let mut readable = &buf[..];
+        match read::unsigned(&mut readable) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_signed_not_enough_data() {
+        let buf = [CONTINUATION_BIT];
+        let mut readable = &buf[..];
+        match read::signed(&mut readable) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_signed_truncated_field() {
+        // -129 encoded in two bytes, but framed in a one byte field.
+        let buf = [0x7fu8 | CONTINUATION_BIT, 0x7e];
+        let mut field = io::Read::take(&buf[..], 1);
+        match read::signed(&mut field) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::signed_fixed_width(&mut &buf[..], 1) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(read::signed_fixed_width(&mut &buf[..], 2).expect("Should read number"),
+                   -129);
+    }
+
+    #[test]
+    fn test_write_unsigned_not_enough_space() {
+        let mut buf = [0; 1];
+        let mut writable = &mut buf[..];
+        match write::unsigned(&mut writable, 128) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::WriteZero),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_write_signed_not_enough_space() {
+        let mut buf = [0; 1];
+        let mut writable = &mut buf[..];
+        match write::signed(&mut writable, 128) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::WriteZero),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn dogfood_signed() {
+        fn inner(i: i64) {
+            let mut buf = [0u8; 1024];
+
+            {
+                let mut writable = &mut buf[..];
+                write::signed(&mut writable, i).expect("Should write signed number");
+            }
+
+            let mut readable = &buf[..];
+            let result = read::signed(&mut readable).expect("Should be able to read it back again");
+            assert_eq!(i, result);
+        }
+        for i in -513..513 {
+            inner(i);
+        }
+        inner(i64::MIN);
+    }
+
+    #[test]
+    fn dogfood_unsigned() {
+        for i in 0..1025 {
+            let mut buf = [0u8; 1024];
+
+            {
+                let mut writable = &mut buf[..];
+                write::unsigned(&mut writable, i).expect("Should write signed number");
+            }
+
+            let mut readable = &buf[..];
+            let result = read::unsigned(&mut readable)
+                .expect("Should be able to read it back again");
+            assert_eq!(i, result);
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_overflow() {
+        let buf = [2u8 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   1];
+        let mut readable = &buf[..];
+        assert!(read::unsigned(&mut readable).is_err());
+    }
+
+    #[test]
+    fn test_read_error_display() {
+        assert_eq!(read::Error::Overflow.to_string(),
+                   "leb128::read::Error: The number being read is larger than can be represented");
+
+        let err = read::Error::IoError(io::Error::new(io::ErrorKind::InvalidData, "boom"));
+        assert_eq!(err.to_string(), "leb128::read::Error: boom");
+    }
+
+    #[test]
+    fn test_read_unsigned_or_bytes() {
+        for &val in &[0u64, 1, 127, 128, 12857, u64::MAX] {
+            let mut buf = vec![];
+            write::unsigned(&mut buf, val).expect("Should write number");
+            assert_eq!(read::unsigned_or_bytes(&mut &buf[..], None).expect("Should read number"),
+                       Ok(val));
+        }
+
+        // Zero padding past bit 63 still fits.
+        let buf = [0x81, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00];
+        assert_eq!(read::unsigned_or_bytes(&mut &buf[..], None).expect("Should read number"),
+                   Ok(1));
+
+        let mut readable = &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02, 0x05][..];
+        assert_eq!(read::unsigned_or_bytes(&mut readable, None).expect("Should read number"),
+                   Err(vec![0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02]));
+        assert_eq!(readable, [0x05]);
+
+        let buf = [0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x01];
+        assert_eq!(read::unsigned_or_bytes(&mut &buf[..], None).expect("Should read number"),
+                   Err(buf.to_vec()));
+
+        assert_eq!(read::unsigned_or_bytes(&mut &buf[..11], None).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        assert_eq!(read::unsigned_or_bytes(&mut &buf[..], Some(12)).expect("Should read number"),
+                   Err(buf.to_vec()));
+        assert_eq!(read::unsigned_or_bytes(&mut &buf[..], Some(11)).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+
+        // A long run of zero groups, far past any shift that fits in a `u64`.
+        let mut buf = vec![0x80; 1000];
+        buf.push(0x00);
+        assert_eq!(read::unsigned_or_bytes(&mut &buf[..], None).expect("Should read number"),
+                   Ok(0));
+        assert_eq!(read::unsigned_or_bytes(&mut &buf[..], Some(1000)).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+    }
+
+    #[test]
+    fn test_read_unsigned_wrapping() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 300).expect("Should write number");
+        assert_eq!(read::unsigned_wrapping(&mut &buf[..], 8).expect("Should read number"), 44);
+        assert_eq!(read::unsigned_wrapping(&mut &buf[..], 9).expect("Should read number"),
+                   300);
+        assert_eq!(read::unsigned_wrapping(&mut &buf[..], 64).expect("Should read number"),
+                   300);
+
+        let mut buf = vec![];
+        write::unsigned(&mut buf, u64::MAX).expect("Should write number");
+        assert_eq!(read::unsigned_wrapping(&mut &buf[..], 1).expect("Should read number"), 1);
+        assert_eq!(read::unsigned_wrapping(&mut &buf[..], 64).expect("Should read number"),
+                   u64::MAX);
+
+        // Bits beyond the 64th are discarded rather than overflowing.
+        let mut readable = &[0x85, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f, 3][..];
+        assert_eq!(read::unsigned_wrapping(&mut readable, 32).expect("Should read number"), 5);
+        assert_eq!(readable, [3]);
+
+        // Many more continuation bytes than a `u64` ever needs.
+        let mut buf = vec![0xff];
+        buf.extend_from_slice(&[0xff; 20]);
+        buf.push(0x01);
+        assert_eq!(read::unsigned_wrapping(&mut &buf[..], 16).expect("Should read number"),
+                   0xffff);
+        assert_eq!(read::unsigned_wrapping(&mut &buf[..], 64).expect("Should read number"),
+                   u64::MAX);
+
+        assert_eq!(read::unsigned_wrapping(&mut &[0x80][..], 8).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_read_signed_overflow() {
+        let buf = [2u8 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   2 | CONTINUATION_BIT,
+                   1];
+        let mut readable = &buf[..];
+        assert!(read::signed(&mut readable).is_err());
+    }
+
+    #[test]
+    fn test_zigzag() {
+        for &(val, encoded) in &[(0i64, 0u64), (-1, 1), (1, 2), (-2, 3), (2, 4),
+                                 (i64::MAX, u64::MAX - 1), (i64::MIN, u64::MAX)] {
+            let mut buf = vec![];
+            write::zigzag(&mut buf, val).expect("Should write number");
+
+            let mut expected = vec![];
+            write::unsigned(&mut expected, encoded).expect("Should write number");
+            assert_eq!(buf, expected);
+
+            assert_eq!(read::zigzag(&mut &buf[..]).expect("Should read number"), val);
+        }
+    }
+
+    #[test]
+    fn test_net_7bit_i32() {
+        // Bytes produced by .NET's `BinaryWriter.Write7BitEncodedInt`.
+        for &(val, expected) in &[(0i32, &[0x00u8][..]),
+                                  (127, &[0x7f]),
+                                  (300, &[0xac, 0x02]),
+                                  (i32::MAX, &[0xff, 0xff, 0xff, 0xff, 0x07]),
+                                  (-1, &[0xff, 0xff, 0xff, 0xff, 0x0f]),
+                                  (i32::MIN, &[0x80, 0x80, 0x80, 0x80, 0x08])] {
+            let mut buf = vec![];
+            assert_eq!(write::net_7bit_i32(&mut buf, val).expect("Should write number"),
+                       expected.len());
+            assert_eq!(buf, expected);
+            assert_eq!(read::net_7bit_i32(&mut &buf[..]).expect("Should read number"), val);
+        }
+
+        for buf in &[&[0xffu8, 0xff, 0xff, 0xff, 0x10][..], &[0x80, 0x80, 0x80, 0x80, 0x80, 0x00]] {
+            assert_eq!(read::net_7bit_i32(&mut &buf[..]).unwrap_err().kind(),
+                       read::ErrorKind::Overflow);
+        }
+        assert_eq!(read::net_7bit_i32(&mut &[0x80u8][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_write_in_range() {
+        let mut buf = vec![];
+        assert_eq!(write::unsigned_in_range(&mut buf, 128, 128).expect("Should write number"),
+                   2);
+        match write::unsigned_in_range(&mut buf, 129, 128) {
+            Err(write::Error::OutOfRange) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(buf, [CONTINUATION_BIT, 1]);
+
+        let mut buf = vec![];
+        assert_eq!(write::signed_in_range(&mut buf, -64, -64, 63).expect("Should write number"),
+                   1);
+        assert_eq!(write::signed_in_range(&mut buf, 63, -64, 63).expect("Should write number"),
+                   1);
+        for &val in &[-65, 64, i64::MIN, i64::MAX] {
+            match write::signed_in_range(&mut buf, val, -64, 63) {
+                Err(write::Error::OutOfRange) => {}
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+        assert_eq!(buf, [0x40, 0x3f]);
+
+        let err = io::Error::from(write::Error::OutOfRange);
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_write_signed_into() {
+        for &val in &[0i64, 63, -64, 64, -65, -12857, i64::MIN, i64::MAX] {
+            let len = write::signed_len(val);
+
+            let mut buf = vec![0; len];
+            let mut expected = vec![];
+            write::signed(&mut expected, val).expect("Should write number");
+            assert_eq!(write::signed_into(&mut buf, val).expect("Should write number"),
+                       &expected[..]);
+
+            let mut buf = vec![0; len - 1];
+            match write::signed_into(&mut buf, val) {
+                Err(write::Error::BufferTooSmall) => {}
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+            assert!(buf.iter().all(|&byte| byte == 0));
+        }
+
+        let mut buf = [0xff; 4];
+        assert_eq!(write::signed_into(&mut buf, -65).expect("Should write number"),
+                   [0xbf, 0x7f]);
+        assert_eq!(buf, [0xbf, 0x7f, 0xff, 0xff]);
+
+        let err = io::Error::from(write::Error::BufferTooSmall);
+        assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+    }
+
+    #[test]
+    fn test_sign_magnitude() {
+        for &(val, expected) in &[(0i64, &[0u8, 0][..]),
+                                  (1, &[0, 1]),
+                                  (-1, &[1, 1]),
+                                  (-128, &[1, CONTINUATION_BIT, 1]),
+                                  (i64::MIN, &[1, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+                                               0x80, 0x80, 0x01]),
+                                  (i64::MAX, &[0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                                               0xff, 0x7f])] {
+            let mut buf = vec![];
+            assert_eq!(write::sign_magnitude(&mut buf, val).expect("Should write number"),
+                       expected.len());
+            assert_eq!(buf, expected);
+            assert_eq!(read::sign_magnitude(&mut &buf[..]).expect("Should read number"), val);
+        }
+
+        let mut buf = vec![0];
+        write::unsigned(&mut buf, 1 << 63).expect("Should write number");
+        assert_eq!(read::sign_magnitude(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        buf[0] = 1;
+        assert_eq!(read::sign_magnitude(&mut &buf[..]).expect("Should read number"),
+                   i64::MIN);
+
+        let mut buf = vec![1];
+        write::unsigned(&mut buf, (1 << 63) + 1).expect("Should write number");
+        assert_eq!(read::sign_magnitude(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+
+        // An invalid sign byte is rejected without consuming the magnitude.
+        let mut readable = &[2u8, 1][..];
+        match read::sign_magnitude(&mut readable) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(readable, [1]);
+
+        match read::sign_magnitude(&mut &[1u8, 0][..]) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_tagged_integer() {
+        for &val in &[0i128, 1, 127, 128, -1, -65, i64::MIN as i128, u64::MAX as i128] {
+            let mut buf = vec![];
+            let len = write::tagged_integer(&mut buf, val).expect("Should write number");
+            assert_eq!(len, buf.len());
+            assert_eq!(buf[0], (val < 0) as u8);
+            assert_eq!(read::tagged_integer(&mut &buf[..]).expect("Should read number"), val);
+        }
+
+        // A non-negative number may also be written with the signed codec.
+        assert_eq!(read::tagged_integer(&mut &[1u8, 0x3f][..]).expect("Should read number"),
+                   63);
+
+        let mut buf = vec![];
+        for &val in &[i64::MIN as i128 - 1, u64::MAX as i128 + 1] {
+            match write::tagged_integer(&mut buf, val) {
+                Err(write::Error::OutOfRange) => {}
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+        assert!(buf.is_empty());
+
+        match read::tagged_integer(&mut &[2u8, 1][..]) {
+            Err(read::Error::UnknownDiscriminant(2)) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(read::tagged_integer(&mut &[0u8][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_u128_halves() {
+        fn encode(mut val: u128) -> Vec<u8> {
+            let mut buf = vec![];
+            loop {
+                let byte = (val & 0x7f) as u8;
+                val >>= 7;
+                if val == 0 {
+                    buf.push(byte);
+                    return buf;
+                }
+                buf.push(byte | CONTINUATION_BIT);
+            }
+        }
+
+        let mut vals = vec![0u128, 1, 127, 128, u128::MAX];
+        for shift in 56..72 {
+            vals.push(1 << shift);
+            vals.push((1 << shift) - 1);
+            vals.push((1 << shift) | 0x5a5a);
+        }
+        vals.push(u64::MAX as u128 * 12857);
+
+        for &val in &vals {
+            let (low, high) = (val as u64, (val >> 64) as u64);
+            let mut buf = vec![];
+            assert_eq!(write::unsigned_u128_halves(&mut buf, low, high)
+                           .expect("Should write number"),
+                       buf.len());
+            assert_eq!(buf, encode(val));
+            assert_eq!(read::unsigned_u128_halves(&mut &buf[..]).expect("Should read number"),
+                       (low, high));
+        }
+
+        let mut buf = encode(u128::MAX);
+        *buf.last_mut().unwrap() = 0x04;
+        assert_eq!(read::unsigned_u128_halves(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        *buf.last_mut().unwrap() = 0x83;
+        assert_eq!(read::unsigned_u128_halves(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(read::unsigned_u128_halves(&mut &buf[..5]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[cfg(feature = "bigint")]
+    #[test]
+    fn test_big_unsigned() {
+        use num_bigint::BigUint;
+
+        for &i in &[0u64, 1, 127, 128, 12857, u64::MAX] {
+            let mut buf = vec![];
+            assert_eq!(write::big_unsigned(&mut buf, &BigUint::from(i))
+                           .expect("Should write number"),
+                       write::unsigned_len(i));
+            assert_eq!(read::unsigned(&mut &buf[..]).expect("Should read number"), i);
+            assert_eq!(read::big_unsigned(&mut &buf[..], None).expect("Should read number"),
+                       BigUint::from(i));
+        }
+
+        // A 40 byte number, 280 bits wide.
+        let val = (BigUint::from(1u8) << 279u32) + BigUint::from(12857u32);
+        let mut buf = vec![];
+        assert_eq!(write::big_unsigned(&mut buf, &val).expect("Should write number"),
+                   40);
+        assert_eq!(buf[..2], [57 | CONTINUATION_BIT, 100 | CONTINUATION_BIT]);
+        assert_eq!(buf[39], 0x40);
+        assert_eq!(read::big_unsigned(&mut &buf[..], Some(40)).expect("Should read number"),
+                   val);
+
+        assert_eq!(read::big_unsigned(&mut &buf[..], Some(39)).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+        assert_eq!(read::big_unsigned(&mut &buf[..39], None).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_duration() {
+        use std::time::Duration;
+
+        for &d in &[Duration::new(0, 0),
+                    Duration::new(1, 999_999_999),
+                    Duration::from_millis(12857),
+                    Duration::new(u64::MAX, 999_999_999)] {
+            let mut buf = vec![];
+            assert_eq!(write::duration(&mut buf, d).expect("Should write duration"),
+                       buf.len());
+            assert_eq!(read::duration(&mut &buf[..]).expect("Should read duration"), d);
+        }
+
+        let mut buf = vec![];
+        write::duration(&mut buf, Duration::new(3, 500)).expect("Should write duration");
+        assert_eq!(buf, [3, 0xf4, 0x03]);
+
+        let mut buf = vec![0];
+        write::unsigned(&mut buf, 1_000_000_000).expect("Should write number");
+        assert_eq!(read::duration(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_system_time() {
+        use std::time::{Duration, UNIX_EPOCH};
+
+        for &(t, secs, nanos) in &[(UNIX_EPOCH, 0i64, 0u64),
+                                   (UNIX_EPOCH + Duration::new(12857, 5), 12857, 5),
+                                   (UNIX_EPOCH - Duration::from_secs(2), -2, 0),
+                                   (UNIX_EPOCH - Duration::from_millis(500), -1, 500_000_000),
+                                   (UNIX_EPOCH - Duration::new(3, 1), -4, 999_999_999)] {
+            let mut buf = vec![];
+            assert_eq!(write::system_time(&mut buf, t).expect("Should write time"),
+                       buf.len());
+
+            let mut readable = &buf[..];
+            assert_eq!(read::signed(&mut readable).expect("Should read number"), secs);
+            assert_eq!(read::unsigned(&mut readable).expect("Should read number"), nanos);
+
+            assert_eq!(read::system_time(&mut &buf[..]).expect("Should read time"), t);
+        }
+
+        let mut buf = vec![0];
+        write::unsigned(&mut buf, 1_000_000_000).expect("Should write number");
+        assert_eq!(read::system_time(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_socket_addr() {
+        use std::net::SocketAddr;
+
+        let addr: SocketAddr = "192.0.2.1:80".parse().unwrap();
+        let mut buf = vec![];
+        assert_eq!(write::socket_addr(&mut buf, addr).expect("Should write address"),
+                   6);
+        assert_eq!(buf, [4, 192, 0, 2, 1, 80]);
+        assert_eq!(read::socket_addr(&mut &buf[..]).expect("Should read address"), addr);
+
+        let addr: SocketAddr = "[2001:db8::1]:65535".parse().unwrap();
+        let mut buf = vec![];
+        assert_eq!(write::socket_addr(&mut buf, addr).expect("Should write address"),
+                   20);
+        assert_eq!(buf[0], 6);
+        assert_eq!(read::socket_addr(&mut &buf[..]).expect("Should read address"), addr);
+        assert_eq!(read::socket_addr(&mut &buf[..10]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        let mut buf = vec![4, 127, 0, 0, 1];
+        write::unsigned(&mut buf, 65536).expect("Should write number");
+        assert_eq!(read::socket_addr(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+
+        match read::socket_addr(&mut &[5u8, 0][..]) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_detailed_overflow() {
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        match read::unsigned_detailed(&mut &buf[..]) {
+            Err(read::DetailedError::Overflow(o)) => {
+                assert_eq!(o,
+                           read::Overflow {
+                               bytes_read: 10,
+                               partial: u64::MAX >> 1,
+                           })
+            }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01];
+        assert_eq!(read::unsigned_detailed(&mut &buf[..]).expect("Should read number"),
+                   u64::MAX);
+    }
+
+    #[test]
+    fn test_read_unsigned_from_ptr() {
+        for &i in &[0u64, 1, 127, 128, 12857, u64::MAX] {
+            // Offset the number by one byte so that it is unaligned, and leave
+            // a trailing byte past `end` that must not be read.
+            let mut buf = vec![0xff];
+            let len = write::unsigned(&mut buf, i).expect("Should write number");
+            buf.push(0xff);
+
+            let start = buf[1..].as_ptr();
+            unsafe {
+                let end = start.add(len);
+                assert_eq!(read::unsigned_from_ptr(start, end).expect("Should read number"),
+                           (i, end));
+
+                match read::unsigned_from_ptr(start, end.sub(1)) {
+                    Err(read::Error::IoError(e)) => {
+                        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof)
+                    }
+                    otherwise => panic!("Unexpected: {:?}", otherwise),
+                }
+            }
+        }
+
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        let range = buf.as_ptr_range();
+        match unsafe { read::unsigned_from_ptr(range.start, range.end) } {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_peek_unsigned() {
+        use std::io::BufRead;
+
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+        write::unsigned(&mut buf, 1).expect("Should write number");
+
+        let mut readable = io::BufReader::with_capacity(2, &buf[..]);
+        assert_eq!(read::peek_unsigned(&mut readable).expect("Should peek number"),
+                   Some(12857));
+        assert_eq!(read::peek_unsigned(&mut readable).expect("Should peek number"),
+                   Some(12857));
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 12857);
+        assert_eq!(read::peek_unsigned(&mut readable).expect("Should peek number"), Some(1));
+        readable.consume(1);
+        assert_eq!(read::peek_unsigned(&mut readable).expect("Should peek number"), None);
+
+        // Only part of the number fits in the buffer.
+        let mut readable = io::BufReader::with_capacity(1, &buf[..]);
+        assert_eq!(read::peek_unsigned(&mut readable).expect("Should peek number"), None);
+
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(read::peek_unsigned(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_read_unsigned_with_remaining() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+        buf.extend_from_slice(&[1, 2, 3, 4, 5]);
+
+        let mut readable = io::BufReader::with_capacity(4, &buf[..]);
+        assert_eq!(read::unsigned_with_remaining(&mut readable).expect("Should read number"),
+                   (12857, 2));
+
+        // The number used up the buffer, so it is refilled.
+        let mut readable = io::BufReader::with_capacity(2, &buf[..]);
+        assert_eq!(read::unsigned_with_remaining(&mut readable).expect("Should read number"),
+                   (12857, 2));
+
+        let mut readable = &[0x7f][..];
+        assert_eq!(read::unsigned_with_remaining(&mut readable).expect("Should read number"),
+                   (127, 0));
+
+        assert_eq!(read::unsigned_with_remaining(&mut &[0x80][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    /// A non-blocking reader that would block wherever `None` appears.
+    struct WouldBlock<'a>(&'a [Option<u8>]);
+
+    impl<'a> io::Read for WouldBlock<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.0.split_first() {
+                None => Ok(0),
+                Some((&next, rest)) => {
+                    self.0 = rest;
+                    match next {
+                        Some(byte) => {
+                            buf[0] = byte;
+                            Ok(1)
+                        }
+                        None => Err(io::ErrorKind::WouldBlock.into()),
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_nonblocking() {
+        let mut readable = WouldBlock(&[Some(0xe5), None, Some(0x8e), None, None, Some(0x26),
+                                        Some(0x05), None, Some(0x80)]);
+        let mut state = read::Decoder::new();
+        assert_eq!(read::unsigned_nonblocking(&mut readable, &mut state)
+                       .expect("Should read number"),
+                   None);
+        assert!(state.in_progress());
+        for _ in 0..2 {
+            assert_eq!(read::unsigned_nonblocking(&mut readable, &mut state)
+                           .expect("Should read number"),
+                       None);
+        }
+        assert_eq!(read::unsigned_nonblocking(&mut readable, &mut state)
+                       .expect("Should read number"),
+                   Some(624485));
+        assert!(!state.in_progress());
+        assert_eq!(read::unsigned_nonblocking(&mut readable, &mut state)
+                       .expect("Should read number"),
+                   Some(5));
+        assert_eq!(read::unsigned_nonblocking(&mut readable, &mut state)
+                       .expect("Should read number"),
+                   None);
+        assert_eq!(read::unsigned_nonblocking(&mut readable, &mut state).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert!(!state.in_progress());
+
+        let mut buf = vec![];
+        write::unsigned(&mut buf, u64::MAX).expect("Should write number");
+        let mut state = read::Decoder::new();
+        assert_eq!(read::unsigned_nonblocking(&mut &buf[..], &mut state)
+                       .expect("Should read number"),
+                   Some(u64::MAX));
+
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(read::unsigned_nonblocking(&mut &buf[..], &mut state).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert!(!state.in_progress());
+    }
+
+    #[test]
+    fn test_dyn_fn_pointers() {
+        struct Codec {
+            write: fn(&mut dyn io::Write, i64) -> Result<usize, io::Error>,
+            read: fn(&mut dyn io::Read) -> Result<i64, read::Error>,
+        }
+
+        let codec = Codec {
+            write: write::signed_dyn,
+            read: read::signed_dyn,
+        };
+        let mut buf = vec![];
+        assert_eq!((codec.write)(&mut buf, -12857).expect("Should write number"), 3);
+        let mut readable: &mut dyn io::Read = &mut &buf[..];
+        assert_eq!((codec.read)(readable).expect("Should read number"), -12857);
+        assert!((codec.read)(&mut readable).is_err());
+
+        let write: fn(&mut dyn io::Write, u64) -> Result<usize, io::Error> = write::unsigned_dyn;
+        let read: fn(&mut dyn io::Read) -> Result<u64, read::Error> = read::unsigned_dyn;
+        let mut buf = vec![];
+        write(&mut buf, 12857).expect("Should write number");
+        assert_eq!(read(&mut &buf[..]).expect("Should read number"), 12857);
+    }
+
+    #[test]
+    fn test_read_at_offset() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+        write::signed(&mut buf, -65).expect("Should write number");
+        write::unsigned(&mut buf, 1).expect("Should write number");
+        buf.push(CONTINUATION_BIT);
+
+        let mut offset = 0;
+        assert_eq!(read::unsigned_at_offset(&buf, &mut offset).expect("Should read number"),
+                   12857);
+        assert_eq!(offset, 2);
+        assert_eq!(read::signed_at_offset(&buf, &mut offset).expect("Should read number"),
+                   -65);
+        assert_eq!(offset, 4);
+        assert_eq!(read::unsigned_at_offset(&buf, &mut offset).expect("Should read number"),
+                   1);
+        assert_eq!(offset, 5);
+
+        assert_eq!(read::unsigned_at_offset(&buf, &mut offset).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(offset, 5);
+
+        let mut offset = 10;
+        assert_eq!(read::signed_at_offset(&buf, &mut offset).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(offset, 10);
+    }
+
+    /// A reader that fails or returns `Ok(0)` once before producing data.
+    struct Hiccup<'a> {
+        hiccup: Option<io::Result<usize>>,
+        data: &'a [u8],
+    }
+
+    impl<'a> io::Read for Hiccup<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            match self.hiccup.take() {
+                Some(result) => result,
+                None => self.data.read(buf),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_retries_interrupted() {
+        let mut readable = Hiccup {
+            hiccup: Some(Err(io::Error::from(io::ErrorKind::Interrupted))),
+            data: &[CONTINUATION_BIT, 1],
+        };
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 128);
+
+        let mut readable = Hiccup {
+            hiccup: Some(Err(io::Error::from(io::ErrorKind::Interrupted))),
+            data: &[0x7f],
+        };
+        assert_eq!(read::signed(&mut readable).expect("Should read number"), -1);
+    }
+
+    #[test]
+    fn test_read_zero_is_end_of_input() {
+        let mut readable = Hiccup {
+            hiccup: Some(Ok(0)),
+            data: &[CONTINUATION_BIT, 1],
+        };
+        assert_eq!(read::unsigned(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        // The data is still there for a later read.
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 128);
+
+        let mut readable = Hiccup {
+            hiccup: Some(Ok(0)),
+            data: &[0x7f],
+        };
+        assert_eq!(read::signed(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    /// A most significant bit first bitstream over a byte slice.
+    struct Bits<'a> {
+        data: &'a [u8],
+        pos: usize,
+    }
+
+    impl<'a> Bits<'a> {
+        fn bit(&mut self) -> io::Result<u8> {
+            let byte = self.data.get(self.pos / 8).ok_or(io::ErrorKind::UnexpectedEof)?;
+            let bit = (byte >> (7 - self.pos % 8)) & 1;
+            self.pos += 1;
+            Ok(bit)
+        }
+    }
+
+    impl<'a> read::BitRead for Bits<'a> {
+        fn read_byte(&mut self) -> io::Result<u8> {
+            let mut byte = 0;
+            for _ in 0..8 {
+                byte = (byte << 1) | self.bit()?;
+            }
+            Ok(byte)
+        }
+    }
+
+    #[test]
+    fn test_read_from_bits() {
+        let mut encoded = vec![];
+        write::unsigned(&mut encoded, 12857).expect("Should write number");
+        write::signed(&mut encoded, -65).expect("Should write number");
+
+        // Three flag bits, then the numbers, then padding.
+        let mut data = vec![0b101 << 5];
+        for &byte in &encoded {
+            *data.last_mut().unwrap() |= byte >> 3;
+            data.push(byte << 5);
+        }
+
+        let mut bits = Bits { data: &data, pos: 0 };
+        assert_eq!((bits.bit().unwrap(), bits.bit().unwrap(), bits.bit().unwrap()),
+                   (1, 0, 1));
+        assert_eq!(read::unsigned_from_bits(&mut bits).expect("Should read number"), 12857);
+        assert_eq!(read::signed_from_bits(&mut bits).expect("Should read number"), -65);
+        assert_eq!(bits.pos, 3 + 8 * encoded.len());
+
+        let mut bits = Bits { data: &data[..2], pos: 3 };
+        assert_eq!(read::unsigned_from_bits(&mut bits).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_split() {
+        let mut buf = vec![];
+        for &i in &[0u64, 127, 128, 12857, u64::MAX] {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+
+        let slices = read::split(&buf).expect("Should split numbers");
+        assert_eq!(slices.iter().map(|slice| slice.len()).collect::<Vec<_>>(),
+                   [1, 1, 2, 2, 10]);
+        assert_eq!(slices.concat(), buf);
+        for (slice, offset) in slices.iter().zip(read::boundaries(&buf).unwrap()) {
+            assert_eq!(slice.as_ptr(), buf[offset..].as_ptr());
+        }
+
+        assert_eq!(read::split_n(&buf, 3).expect("Should split numbers"),
+                   [&[0][..], &[0x7f], &[CONTINUATION_BIT, 1]]);
+        assert_eq!(read::split_n(&buf[..buf.len() - 1], 4).expect("Should split numbers").len(),
+                   4);
+        assert!(read::split(&[]).expect("Should split numbers").is_empty());
+
+        assert_eq!(read::split(&buf[..buf.len() - 1]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_read_lazy() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+        write::signed(&mut buf, -65).expect("Should write number");
+        buf.extend_from_slice(&[0xff; 10]);
+        buf.push(0x02);
+
+        let (first, rest) = read::lazy(&buf).expect("Should locate number");
+        assert_eq!(first.as_bytes(), &buf[..2]);
+        assert_eq!(first.get_u64().expect("Should decode number"), 12857);
+
+        let (second, rest) = read::lazy(rest).expect("Should locate number");
+        assert_eq!(second.get_i64().expect("Should decode number"), -65);
+
+        // An overflowing number is only reported when decoded.
+        let (third, rest) = read::lazy(rest).expect("Should locate number");
+        assert_eq!(third.as_bytes().len(), 11);
+        assert_eq!(third.get_u64().unwrap_err().kind(), read::ErrorKind::Overflow);
+        assert!(rest.is_empty());
+
+        assert_eq!(read::lazy(&[CONTINUATION_BIT]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(read::lazy(&[]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_read_collect() {
+        let mut buf = vec![];
+        for &i in &[0u64, 1, 127, 128, u64::MAX] {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+        assert_eq!(read::unsigned_collect(&mut &buf[..]).expect("Should read numbers"),
+                   [0, 1, 127, 128, u64::MAX]);
+
+        let mut buf = vec![];
+        for &i in &[0i64, -1, 63, -65, i64::MIN] {
+            write::signed(&mut buf, i).expect("Should write number");
+        }
+        assert_eq!(read::signed_collect(&mut &buf[..]).expect("Should read numbers"),
+                   [0, -1, 63, -65, i64::MIN]);
+
+        assert!(read::unsigned_collect(&mut &[][..]).expect("Should read numbers").is_empty());
+    }
+
+    #[test]
+    fn test_read_fold() {
+        let mut buf = vec![];
+        for &i in &[1u64, 127, 128, 12857] {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+        assert_eq!(read::unsigned_fold(&mut &buf[..], 0, |acc, val| acc + val)
+                       .expect("Should read numbers"),
+                   1 + 127 + 128 + 12857);
+        assert_eq!(read::unsigned_fold(&mut &[][..], 7, |acc, val| acc + val)
+                       .expect("Should read numbers"),
+                   7);
+        assert_eq!(read::unsigned_fold(&mut &buf[..buf.len() - 1], 0, |acc, val| acc + val)
+                       .unwrap_err()
+                       .kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        let mut buf = vec![];
+        for &i in &[-1i64, 63, -65, 12857] {
+            write::signed(&mut buf, i).expect("Should write number");
+        }
+        assert_eq!(read::signed_fold(&mut &buf[..], 0, |acc, val| acc + val)
+                       .expect("Should read numbers"),
+                   -1 + 63 - 65 + 12857);
+    }
+
+    #[test]
+    fn test_read_unsigned_add_to() {
+        let mut buf = vec![];
+        for &i in &[1u64, 12857, u64::MAX - 12858, 1] {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+
+        let mut readable = &buf[..];
+        let mut acc = 0;
+        for _ in 0..3 {
+            read::unsigned_add_to(&mut readable, &mut acc).expect("Should add number");
+        }
+        assert_eq!(acc, u64::MAX);
+
+        // The sum wraps, so the accumulator is left as it was.
+        match read::unsigned_add_to(&mut readable, &mut acc) {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(acc, u64::MAX);
+
+        let mut acc = 0;
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(read::unsigned_add_to(&mut &buf[..], &mut acc).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(read::unsigned_add_to(&mut &[0x80][..], &mut acc).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(acc, 0);
+    }
+
+    #[test]
+    fn test_read_from_take() {
+        use std::io::Read;
+
+        let mut buf = vec![];
+        for &i in &[1u64, 12857, 127, u64::MAX] {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+
+        // The section ends on a number boundary.
+        let mut readable = &buf[..];
+        assert_eq!(read::from_take((&mut readable).take(4)).expect("Should read numbers"),
+                   [1, 12857, 127]);
+        assert_eq!(readable.len(), 10);
+
+        // The section ends in the middle of a number.
+        match read::from_take((&buf[..]).take(2)) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        assert!(read::from_take((&buf[..]).take(0)).expect("Should read numbers").is_empty());
+        assert_eq!(read::from_take((&buf[..]).take(100)).expect("Should read numbers").len(),
+                   4);
+    }
+
+    #[test]
+    fn test_read_collect_not_enough_data() {
+        let buf = [1u8, CONTINUATION_BIT];
+        match read::unsigned_collect(&mut &buf[..]) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::signed_collect(&mut &buf[..]) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_detailed_not_enough_data() {
+        let buf = [CONTINUATION_BIT, CONTINUATION_BIT];
+        match read::unsigned_detailed(&mut &buf[..]) {
+            Err(e @ read::DetailedError::UnexpectedEndOfData { .. }) => {
+                assert_eq!(e.consumed(), Some(2))
+            }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::signed_detailed(&mut &buf[..1]) {
+            Err(e @ read::DetailedError::UnexpectedEndOfData { .. }) => {
+                assert_eq!(e.consumed(), Some(1))
+            }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::signed_detailed(&mut &[][..]) {
+            Err(read::DetailedError::UnexpectedEndOfData { consumed: 0 }) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_detailed_overflow_at_byte() {
+        // A ten byte number whose tenth byte sets a bit above bit 63.
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x03];
+        match read::unsigned_detailed(&mut &buf[..]) {
+            Err(read::DetailedError::Overflow(o)) => assert_eq!(o.at_byte(), 9),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::signed_detailed(&mut &buf[..]) {
+            Err(read::DetailedError::Overflow(o)) => assert_eq!(o.at_byte(), 9),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        // A tenth byte that is not the last.
+        let buf = [0x80u8; 12];
+        match read::unsigned_detailed(&mut &buf[..]) {
+            Err(e @ read::DetailedError::Overflow(_)) => {
+                assert!(e.to_string().contains("byte index 9"), "{}", e)
+            }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_signed_detailed_overflow() {
+        let buf = [0x80u8, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x81, 0x01];
+        match read::signed_detailed(&mut &buf[..]) {
+            Err(read::DetailedError::Overflow(o)) => {
+                assert_eq!(o,
+                           read::Overflow {
+                               bytes_read: 10,
+                               partial: 1 << 56,
+                           })
+            }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_resynchronize_after_overflow() {
+        let mut buf = vec![0xffu8; 9];
+        buf.push(0x02);
+        buf.push(0x05);
+        let mut readable = &buf[..];
+        let err = read::unsigned_detailed(&mut readable).unwrap_err();
+        assert_eq!(err.consumed(), Some(10));
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 5);
+    }
+
+    #[test]
+    fn test_read_unsigned_many_continuation_bytes() {
+        let buf = [CONTINUATION_BIT; 20];
+        match read::unsigned(&mut &buf[..]) {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::unsigned_detailed(&mut &buf[..]) {
+            Err(read::DetailedError::Overflow(o)) => {
+                assert_eq!(o,
+                           read::Overflow {
+                               bytes_read: 10,
+                               partial: 0,
+                           })
+            }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_tabled() {
+        let vals = boundary_values();
+        let mut buf = vec![];
+        for &i in &vals {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+        let mut readable = &buf[..];
+        for &i in &vals {
+            assert_eq!(read::unsigned_tabled(&mut readable).expect("Should read number"), i);
+        }
+        assert!(readable.is_empty());
+
+        assert_eq!(read::unsigned_tabled(&mut &[CONTINUATION_BIT][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(read::unsigned_tabled(&mut &[][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_read_unsigned_around_max() {
+        for &(low, high, expected) in &[(u64::MAX, 0, Some(u64::MAX)),
+                                        (0, 1, None),
+                                        (1, 1, None),
+                                        (u64::MAX, u64::MAX, None)] {
+            let mut buf = vec![];
+            write::unsigned_u128_halves(&mut buf, low, high).expect("Should write number");
+            assert_eq!(read::unsigned(&mut &buf[..]).ok(), expected);
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_tenth_byte() {
+        let mut buf = [0xffu8; 10];
+        for last in 0..=u8::MAX {
+            buf[9] = last;
+            match (last, read::unsigned(&mut &buf[..])) {
+                (0x00, Ok(val)) => assert_eq!(val, u64::MAX >> 1),
+                (0x01, Ok(val)) => assert_eq!(val, u64::MAX),
+                (_, Err(read::Error::Overflow)) => {}
+                (_, otherwise) => panic!("Unexpected for {:#x}: {:?}", last, otherwise),
+            }
+        }
+
+        let mut buf = [CONTINUATION_BIT; 10];
+        buf[9] = 0x01;
+        assert_eq!(read::unsigned(&mut &buf[..]).expect("Should read number"), 1 << 63);
+        buf[9] = 0x02;
+        match read::unsigned(&mut &buf[..]) {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_encoded_readers() {
+        use std::io::Read;
+
+        for &i in &[0u64, 127, 128, 12857, u64::MAX] {
+            let mut expected = vec![];
+            write::unsigned(&mut expected, i).expect("Should write number");
+
+            let mut buf = vec![];
+            write::unsigned_reader(i).read_to_end(&mut buf).expect("Should read encoding");
+            assert_eq!(buf, expected);
+        }
+
+        for &i in &[0i64, -1, 63, -65, i64::MIN, i64::MAX] {
+            let mut expected = vec![];
+            write::signed(&mut expected, i).expect("Should write number");
+
+            let mut buf = vec![];
+            write::signed_reader(i).read_to_end(&mut buf).expect("Should read encoding");
+            assert_eq!(buf, expected);
+        }
+
+        let mut chained = write::unsigned_reader(130).chain(write::signed_reader(-2));
+        assert_eq!(read::unsigned(&mut chained).expect("Should read number"), 130);
+        assert_eq!(read::signed(&mut chained).expect("Should read number"), -2);
+    }
+
+    #[test]
+    fn test_error_kind() {
+        let err = read::unsigned(&mut &[CONTINUATION_BIT][..]).unwrap_err();
+        assert_eq!(err.kind(), read::ErrorKind::UnexpectedEndOfData);
+
+        let err = read::unsigned(&mut &[CONTINUATION_BIT; 10][..]).unwrap_err();
+        assert_eq!(err.kind(), read::ErrorKind::Overflow);
+
+        let err = read::Error::from(io::Error::from(io::ErrorKind::InvalidData));
+        assert_eq!(err.kind(), read::ErrorKind::Io);
+    }
+
+    #[test]
+    fn test_align_padding() {
+        assert_eq!(write::align_padding(0, 4), 0);
+        assert_eq!(write::align_padding(1, 4), 3);
+        assert_eq!(write::align_padding(4, 4), 0);
+        assert_eq!(write::align_padding(5, 4), 3);
+        assert_eq!(write::align_padding(7, 1), 0);
+    }
+
+    #[test]
+    fn test_write_unsigned_aligned() {
+        for &i in &[0u64, 1, 127, 128, 12857, 1 << 35, u64::MAX] {
+            for align in 1..6 {
+                let mut buf = vec![];
+                match write::unsigned_aligned(&mut buf, i, align) {
+                    Ok(len) => {
+                        assert_eq!(len, buf.len());
+                        assert_eq!(len % align, 0);
+                        assert!(len < write::unsigned_len(i) + align);
+                        assert_eq!(read::unsigned(&mut &buf[..]).expect("Should read number"),
+                                   i);
+                    }
+                    Err(e) => {
+                        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+                        assert!(write::unsigned_len(i) + align > 10);
+                    }
+                }
+            }
+        }
+
+        let mut buf = vec![];
+        assert_eq!(write::unsigned_aligned(&mut buf, 2, 4).expect("Should write number"),
+                   4);
+        assert_eq!(buf, [2 | CONTINUATION_BIT, CONTINUATION_BIT, CONTINUATION_BIT, 0]);
+    }
+
+    #[test]
+    fn test_write_unsigned_pow2_padded() {
+        for &(i, expected) in &[(0u64, 1),
+                                (127, 1),
+                                (128, 2),
+                                (1 << 14, 4),
+                                (1 << 28, 8),
+                                ((1 << 56) - 1, 8),
+                                (1 << 56, 9),
+                                (u64::MAX, 10)] {
+            let mut buf = vec![];
+            assert_eq!(write::unsigned_pow2_padded(&mut buf, i).expect("Should write number"),
+                       expected);
+            assert_eq!(buf.len(), expected);
+            assert_eq!(read::unsigned(&mut &buf[..]).expect("Should read number"), i);
+        }
+
+        let mut buf = vec![];
+        write::unsigned_pow2_padded(&mut buf, 624485).expect("Should write number");
+        assert_eq!(buf, [0xe5, 0x8e, 0xa6, 0x00]);
+    }
+
+    #[test]
+    fn test_write_unsigned_min_bytes() {
+        let mut buf = vec![];
+        assert_eq!(write::unsigned_min_bytes(&mut buf, 2, 3).expect("Should write number"),
+                   3);
+        assert_eq!(buf, [2 | CONTINUATION_BIT, CONTINUATION_BIT, 0]);
+
+        let mut buf = vec![];
+        assert_eq!(write::unsigned_min_bytes(&mut buf, 12857, 1).expect("Should write number"),
+                   2);
+        assert_eq!(buf, [57 | CONTINUATION_BIT, 100]);
+
+        for &i in &[0u64, 1, 127, 128, 12857, u64::MAX] {
+            for min_bytes in 0..11 {
+                let mut buf = vec![];
+                write::unsigned_min_bytes(&mut buf, i, min_bytes).expect("Should write number");
+                assert!(buf.len() >= min_bytes);
+                assert_eq!(read::unsigned(&mut &buf[..]).expect("Should read number"), i);
+            }
+        }
+
+        match write::unsigned_min_bytes(&mut vec![], 0, 11) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidInput),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_signed_fixed_width() {
+        let mut buf = vec![];
+        assert_eq!(write::signed_fixed_width(&mut buf, -2, 3).expect("Should write number"),
+                   3);
+        assert_eq!(buf, [0xfe, 0xff, 0x7f]);
+
+        for &(val, bytes) in &[(0i64, 1usize), (63, 1), (-64, 1), (64, 2), (-8192, 2),
+                               (8191, 4), (i64::MIN, 10), (i64::MAX, 10), (-1, 10)] {
+            let mut buf = vec![];
+            write::signed_fixed_width(&mut buf, val, bytes).expect("Should write number");
+            assert_eq!(buf.len(), bytes);
+            assert_eq!(read::signed(&mut &buf[..]).expect("Should read number"), val);
+            assert_eq!(read::signed_fixed_width(&mut &buf[..], bytes)
+                           .expect("Should read number"),
+                       val);
+        }
+    }
+
+    #[test]
+    fn test_signed_fixed_width_invalid() {
+        let mut buf = vec![];
+        for &(val, bytes) in &[(0i64, 0usize), (0, 11), (64, 1), (-65, 1), (8192, 2)] {
+            match write::signed_fixed_width(&mut buf, val, bytes) {
+                Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidInput),
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+        assert!(buf.is_empty());
+
+        match read::signed_fixed_width(&mut &[0x7eu8, 0x00][..], 2) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        match read::signed_fixed_width(&mut &[0xfeu8, 0xff, 0x7f][..], 2) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_exact() {
+        for &i in &[0u64, 1, 127, 128, 12857, u64::MAX] {
+            for len in write::unsigned_len(i)..11 {
+                let mut buf = vec![];
+                write::unsigned_min_bytes(&mut buf, i, len).expect("Should write number");
+                buf.push(0xff);
+                assert_eq!(read::unsigned_exact(&buf, len).expect("Should read number"), i);
+            }
+        }
+
+        for &(buf, len, kind) in &[(&[0x02u8][..], 2, io::ErrorKind::UnexpectedEof),
+                                   (&[0x82, 0x80], 2, io::ErrorKind::InvalidData),
+                                   (&[0x82, 0x00], 1, io::ErrorKind::InvalidData),
+                                   (&[0x02, 0x00], 2, io::ErrorKind::InvalidData),
+                                   (&[0x02], 0, io::ErrorKind::InvalidData)] {
+            match read::unsigned_exact(buf, len) {
+                Err(read::Error::IoError(e)) => assert_eq!(e.kind(), kind),
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(read::unsigned_exact(&buf, 10).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_read_overlong_bytes() {
+        for &i in &[0u64, 1, 127, 128, 12857, u64::MAX] {
+            for len in write::unsigned_len(i)..11 {
+                let mut buf = vec![];
+                write::unsigned_min_bytes(&mut buf, i, len).expect("Should write number");
+                buf.push(0x7f);
+                assert_eq!(read::overlong_bytes(&buf).expect("Should read number"),
+                           len - write::unsigned_len(i));
+            }
+        }
+
+        assert_eq!(read::overlong_bytes(&[0x80]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        let buf = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(read::overlong_bytes(&buf).unwrap_err().kind(), read::ErrorKind::Overflow);
+    }
+
+    /// Values around every 7-bit boundary, where the encoded length changes.
+    fn boundary_values() -> Vec<u64> {
+        let mut vals = vec![0, 1, u64::MAX - 1, u64::MAX];
+        for shift in (7..64).step_by(7) {
+            let boundary = 1u64 << shift;
+            vals.extend_from_slice(&[boundary - 2, boundary - 1, boundary, boundary + 1]);
+        }
+        vals
+    }
+
+    #[test]
+    fn test_unsigned_write_variants_agree() {
+        use std::io::Read;
+
+        for val in boundary_values() {
+            let mut expected = vec![];
+            write::unsigned(&mut expected, val).expect("Should write number");
+
+            let mut buf = [0u8; 16];
+            let len = write::unsigned(&mut &mut buf[..], val).expect("Should write number");
+            assert_eq!(&buf[..len], &expected[..], "stack buffer for {}", val);
+
+            let mut buf = vec![];
+            write::unsigned_reader(val).read_to_end(&mut buf).expect("Should read encoding");
+            assert_eq!(buf, expected, "unsigned_reader for {}", val);
+
+            let mut buf = vec![];
+            write::unsigned_min_bytes(&mut buf, val, 0).expect("Should write number");
+            assert_eq!(buf, expected, "unsigned_min_bytes for {}", val);
+
+            assert_eq!(build::encode_all(&[val]), expected, "encode_all for {}", val);
+
+            let encoded: Vec<u8> = write::UnsignedEncoder::new(val).collect();
+            assert_eq!(encoded, expected, "UnsignedEncoder for {}", val);
+        }
+    }
+
+    #[test]
+    fn test_signed_write_variants_agree() {
+        use std::io::Read;
+
+        for val in boundary_values() {
+            for &val in &[val as i64, (val as i64).wrapping_neg()] {
+                let mut expected = vec![];
+                write::signed(&mut expected, val).expect("Should write number");
+
+                let mut buf = [0u8; 16];
+                let len = write::signed(&mut &mut buf[..], val).expect("Should write number");
+                assert_eq!(&buf[..len], &expected[..], "stack buffer for {}", val);
+
+                let mut buf = vec![];
+                write::signed_reader(val).read_to_end(&mut buf).expect("Should read encoding");
+                assert_eq!(buf, expected, "signed_reader for {}", val);
+
+                let mut buf = vec![];
+                write::signed_fixed_width(&mut buf, val, expected.len())
+                    .expect("Should write number");
+                assert_eq!(buf, expected, "signed_fixed_width for {}", val);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hashed() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::Hasher;
+
+        let mut buf = vec![];
+        let mut write_hasher = DefaultHasher::new();
+        write::unsigned_hashed(&mut buf, 12857, &mut write_hasher).expect("Should write number");
+        write::signed_hashed(&mut buf, -129, &mut write_hasher).expect("Should write number");
+
+        let mut expected = DefaultHasher::new();
+        expected.write(&buf[..2]);
+        expected.write(&buf[2..]);
+        assert_eq!(write_hasher.finish(), expected.finish());
+
+        let mut readable = &buf[..];
+        let mut read_hasher = DefaultHasher::new();
+        assert_eq!(read::unsigned_hashed(&mut readable, &mut read_hasher)
+                       .expect("Should read number"),
+                   12857);
+        assert_eq!(read::signed_hashed(&mut readable, &mut read_hasher)
+                       .expect("Should read number"),
+                   -129);
+        assert_eq!(read_hasher.finish(), expected.finish());
+    }
+
+    /// A hasher that mixes in each `write` call as a unit, so that split
+    /// writes hash differently from one concatenated write.
+    #[derive(Default)]
+    struct CallHasher {
+        calls: Vec<Vec<u8>>,
+    }
+
+    impl ::std::hash::Hasher for CallHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            self.calls.push(bytes.to_vec());
+        }
+
+        fn finish(&self) -> u64 {
+            self.calls.len() as u64
+        }
+    }
+
+    #[test]
+    fn test_hashed_single_write() {
+        let mut buf = vec![];
+        let mut write_hasher = CallHasher::default();
+        write::unsigned_hashed(&mut buf, 624485, &mut write_hasher).expect("Should write number");
+        write::signed_hashed(&mut buf, i64::MIN, &mut write_hasher).expect("Should write number");
+        assert_eq!(write_hasher.calls, [&buf[..3], &buf[3..]]);
+
+        let mut readable = &buf[..];
+        let mut read_hasher = CallHasher::default();
+        assert_eq!(read::unsigned_hashed(&mut readable, &mut read_hasher)
+                       .expect("Should read number"),
+                   624485);
+        assert_eq!(read::signed_hashed(&mut readable, &mut read_hasher)
+                       .expect("Should read number"),
+                   i64::MIN);
+        assert_eq!(read_hasher.calls, write_hasher.calls);
+
+        // Nothing is fed when reading fails.
+        let mut read_hasher = CallHasher::default();
+        assert_eq!(read::unsigned_hashed(&mut &buf[..2], &mut read_hasher).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        let overflow = [0xffu8, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02];
+        assert_eq!(read::unsigned_hashed(&mut &overflow[..], &mut read_hasher)
+                       .unwrap_err()
+                       .kind(),
+                   read::ErrorKind::Overflow);
+        assert!(read_hasher.calls.is_empty());
+    }
+
+    #[test]
+    fn test_unsigned_encoder() {
+        for val in boundary_values() {
+            let mut expected = vec![];
+            write::unsigned(&mut expected, val).expect("Should write number");
+
+            let mut encoder = write::UnsignedEncoder::new(val);
+            let mut bytes = vec![];
+            while let Some(byte) = encoder.next_byte() {
+                bytes.push(byte);
+            }
+            assert_eq!(bytes, expected);
+            assert_eq!(encoder.next_byte(), None);
+
+            assert_eq!(write::UnsignedEncoder::new(val).collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn test_counting_writer() {
+        let mut w = write::CountingWriter::new(vec![]);
+        assert_eq!(w.bytes_written(), 0);
+
+        write::unsigned(&mut w, 12857).expect("Should write number");
+        write::signed(&mut w, -129).expect("Should write number");
+        assert_eq!(w.bytes_written(), 4);
+
+        w.reset();
+        write::unsigned(&mut w, u64::MAX).expect("Should write number");
+        assert_eq!(w.bytes_written(), 10);
+        assert_eq!(w.into_inner().len(), 14);
+
+        let mut buf = [0u8; 1];
+        let mut w = write::CountingWriter::new(&mut buf[..]);
+        assert!(write::unsigned(&mut w, 128).is_err());
+        assert_eq!(w.bytes_written(), 1);
+    }
+
+    #[test]
+    fn test_read_from_try_iter() {
+        let mut iter = vec![Ok(2u8 | CONTINUATION_BIT), Ok(1), Ok(0x7e)].into_iter();
+        assert_eq!(read::unsigned_from_try_iter(&mut iter).expect("Should read number"),
+                   130);
+        assert_eq!(read::signed_from_try_iter(&mut iter).expect("Should read number"),
+                   -2);
+        assert_eq!(read::unsigned_from_try_iter(&mut iter).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        let mut iter = vec![Ok(CONTINUATION_BIT),
+                            Err(io::Error::from(io::ErrorKind::InvalidData)),
+                            Ok(1)]
+            .into_iter();
+        match read::unsigned_from_try_iter(&mut iter) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_encoded_len() {
+        for val in boundary_values() {
+            let mut buf = vec![];
+            write::unsigned(&mut buf, val).expect("Should write number");
+            assert_eq!(write::unsigned_len(val), buf.len(), "unsigned_len for {}", val);
+
+            for &val in &[val as i64, (val as i64).wrapping_neg()] {
+                let mut buf = vec![];
+                write::signed(&mut buf, val).expect("Should write number");
+                assert_eq!(write::signed_len(val), buf.len(), "signed_len for {}", val);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encoded_total_len() {
+        let vals = boundary_values();
+        let mut buf = vec![];
+        for &val in &vals {
+            write::unsigned(&mut buf, val).expect("Should write number");
+        }
+        assert_eq!(write::unsigned_total_len(&vals), buf.len());
+
+        let vals: Vec<i64> = vals.iter()
+            .flat_map(|&val| vec![val as i64, (val as i64).wrapping_neg()])
+            .collect();
+        let mut buf = vec![];
+        for &val in &vals {
+            write::signed(&mut buf, val).expect("Should write number");
+        }
+        assert_eq!(write::signed_total_len(&vals), buf.len());
+
+        assert_eq!(write::unsigned_total_len(&[]), 0);
+        assert_eq!(write::signed_total_len(&[]), 0);
+    }
+
+    #[test]
+    fn test_options_default_is_lenient() {
+        let options = read::Options::new();
+        let buf = [2u8 | CONTINUATION_BIT, CONTINUATION_BIT, 0];
+        assert_eq!(options.unsigned(&mut &buf[..]).expect("Should read number"), 2);
+        assert_eq!(options.signed(&mut &buf[..]).expect("Should read number"), 2);
+
+        for val in boundary_values() {
+            let mut buf = vec![];
+            write::unsigned(&mut buf, val).expect("Should write number");
+            assert_eq!(options.unsigned(&mut &buf[..]).expect("Should read number"), val);
+        }
+
+        let buf = [CONTINUATION_BIT; 11];
+        assert_eq!(options.unsigned(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(options.unsigned(&mut &buf[..3]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_options_strict() {
+        let options = read::Options::new().strict(true);
+        assert_eq!(options.unsigned(&mut &[0u8][..]).expect("Should read number"), 0);
+        assert_eq!(options.unsigned(&mut &[CONTINUATION_BIT, 0][..]).unwrap_err().kind(),
+                   read::ErrorKind::NonCanonical);
+        assert_eq!(options.signed(&mut &[0x7fu8][..]).expect("Should read number"), -1);
+        assert_eq!(options.signed(&mut &[0xffu8, 0x7f][..]).unwrap_err().kind(),
+                   read::ErrorKind::NonCanonical);
+        assert_eq!(options.signed(&mut &[0xffu8, 0][..]).expect("Should read number"),
+                   127);
+    }
+
+    #[test]
+    fn test_write_signed_is_canonical() {
+        for &(val, expected) in &[(0i64, &[0x00u8][..]),
+                                  (-1, &[0x7f]),
+                                  (63, &[0x3f]),
+                                  (64, &[0xc0, 0x00]),
+                                  (-64, &[0x40]),
+                                  (-65, &[0xbf, 0x7f]),
+                                  (8191, &[0xff, 0x3f]),
+                                  (8192, &[0x80, 0xc0, 0x00]),
+                                  (-8192, &[0x80, 0x40]),
+                                  (-8193, &[0xff, 0xbf, 0x7f]),
+                                  (i64::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+                                               0xff, 0x00]),
+                                  (i64::MIN, &[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80,
+                                               0x80, 0x7f])] {
+            let mut buf = vec![];
+            write::signed(&mut buf, val).expect("Should write number");
+            assert_eq!(buf, expected, "encoding of {}", val);
+        }
+
+        let options = read::Options::new().strict(true);
+        let mut vals = vec![i64::MIN, i64::MAX];
+        for shift in (6..64).step_by(7) {
+            let boundary = 1i64 << shift;
+            vals.extend_from_slice(&[boundary - 1, boundary, -boundary, -boundary - 1]);
+        }
+        for &val in &vals {
+            let mut buf = vec![];
+            write::signed(&mut buf, val).expect("Should write number");
+            assert_eq!(write::signed_len(val), buf.len(), "signed_len for {}", val);
+            assert_eq!(options.signed(&mut &buf[..]).expect("Should read number"), val);
+        }
+    }
+
+    #[test]
+    fn test_options_limits() {
+        let options = read::Options::new().max_bytes(Some(2));
+        assert_eq!(options.unsigned(&mut &[0xffu8, 0x7f][..]).expect("Should read number"),
+                   16383);
+        assert_eq!(options.unsigned(&mut &[0x80u8, 0x80, 0x01][..]).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+
+        let options = read::Options::new().bits(8);
+        assert_eq!(options.unsigned(&mut &[0xffu8, 0x01][..]).expect("Should read number"),
+                   255);
+        assert_eq!(options.unsigned(&mut &[0x80u8, 0x02][..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(options.signed(&mut &[0x80u8, 0x7f][..]).expect("Should read number"),
+                   -128);
+        assert_eq!(options.signed(&mut &[0xffu8, 0x7e][..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(options.signed(&mut &[0x80u8, 0x01][..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_sentinel() {
+        let mut buf = vec![];
+        assert_eq!(write::unsigned_with_sentinel(&mut buf, &[3, 128, 1], 0)
+                       .expect("Should write numbers"),
+                   5);
+        assert_eq!(buf, [3, CONTINUATION_BIT, 1, 1, 0]);
+        buf.push(7);
+
+        let mut readable = &buf[..];
+        assert_eq!(read::unsigned_until_sentinel(&mut readable, 0).expect("Should read numbers"),
+                   [3, 128, 1]);
+        assert_eq!(readable, [7]);
+
+        assert_eq!(read::unsigned_until_sentinel(&mut &buf[..], 1).expect("Should read numbers"),
+                   [3, 128]);
+        assert_eq!(read::unsigned_until_sentinel(&mut &buf[..4], 0).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        match write::unsigned_with_sentinel(&mut vec![], &[1, 0], 0) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidInput),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_while() {
+        let mut buf = vec![];
+        for &i in &[3u64, 128, 12857, 1 << 40, 7] {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+
+        let mut readable = &buf[..];
+        assert_eq!(read::unsigned_while(&mut readable, |val| val < 1 << 20)
+                       .expect("Should read numbers"),
+                   [3, 128, 12857]);
+        assert_eq!(readable, [7]);
+
+        // The first number already fails, and is still consumed.
+        let mut readable = &buf[..];
+        assert_eq!(read::unsigned_while(&mut readable, |val| val > 3)
+                       .expect("Should read numbers"),
+                   Vec::<u64>::new());
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 128);
+
+        assert_eq!(read::unsigned_while(&mut &buf[..], |_| true).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_rle_unsigned() {
+        let mut buf = vec![];
+        assert_eq!(write::rle_unsigned(&mut buf, 3, 128, false).expect("Should write pair"),
+                   3);
+        assert_eq!(write::rle_unsigned(&mut buf, 0, 5, true).expect("Should write pair"),
+                   2);
+        assert_eq!(buf, [3, CONTINUATION_BIT, 1, 0, 5]);
+
+        let mut readable = &buf[..];
+        assert_eq!(read::rle_unsigned(&mut readable, false).expect("Should read pair"),
+                   (3, 128));
+        assert_eq!(read::rle_unsigned(&mut readable, true).expect("Should read pair"),
+                   (0, 5));
+        assert!(readable.is_empty());
+
+        let mut readable = &buf[3..];
+        assert_eq!(read::rle_unsigned(&mut readable, false).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedZero);
+        assert!(readable.is_empty());
+
+        assert_eq!(read::rle_unsigned(&mut &buf[..2], true).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        match write::rle_unsigned(&mut vec![], 0, 5, false) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidInput),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_ragged_unsigned() {
+        let rows = vec![vec![1, 128], vec![], vec![u64::MAX]];
+        let mut buf = vec![];
+        assert_eq!(write::ragged_unsigned(&mut buf, &rows).expect("Should write rows"),
+                   buf.len());
+        assert_eq!(&buf[..6], [3, 2, 1, CONTINUATION_BIT, 1, 0]);
+
+        assert_eq!(read::ragged_unsigned(&mut &buf[..], None).expect("Should read rows"),
+                   rows);
+        assert_eq!(read::ragged_unsigned(&mut &buf[..], Some(3)).expect("Should read rows"),
+                   rows);
+        assert_eq!(read::ragged_unsigned(&mut &buf[..], Some(2)).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+        assert_eq!(read::ragged_unsigned(&mut &buf[..buf.len() - 1], None).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        let absurd = [0xffu8, 0xff, 0xff, 0xff, 0x0f];
+        assert_eq!(read::ragged_unsigned(&mut &absurd[..], Some(1024)).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
     }
 
     #[test]
-    fn test_read_unsigned_not_enough_data() {
-        let buf = [CONTINUATION_BIT];
+    fn test_byte_values() {
+        let mut buf = vec![];
+        assert_eq!(write::byte_values(&mut buf, &[]).expect("Should write bytes"), 1);
+        assert_eq!(buf, [0]);
+        assert!(read::byte_values(&mut &buf[..]).expect("Should read bytes").is_empty());
+
+        let mut buf = vec![];
+        assert_eq!(write::byte_values(&mut buf, &[1, 127, 128, 255]).expect("Should write bytes"),
+                   7);
+        assert_eq!(buf, [4, 1, 127, CONTINUATION_BIT, 1, 0xff, 1]);
+        assert_eq!(read::byte_values(&mut &buf[..]).expect("Should read bytes"),
+                   [1, 127, 128, 255]);
+        assert_eq!(read::byte_values(&mut &buf[..5]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+
+        assert_eq!(read::byte_values(&mut &[1u8, CONTINUATION_BIT, 2][..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_options_max_elements() {
+        let rows = vec![vec![1, 2], vec![3]];
+        let mut buf = vec![];
+        write::ragged_unsigned(&mut buf, &rows).expect("Should write rows");
+
+        // Two rows and three numbers.
+        let options = read::Options::new().max_elements(Some(5));
+        assert_eq!(options.ragged_unsigned(&mut &buf[..]).expect("Should read rows"), rows);
+        let options = read::Options::new().max_elements(Some(4));
+        assert_eq!(options.ragged_unsigned(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+
+        let mut buf = vec![];
+        write::byte_values(&mut buf, b"abc").expect("Should write bytes");
+        let options = read::Options::new().max_elements(Some(3));
+        assert_eq!(options.byte_values(&mut &buf[..]).expect("Should read bytes"), b"abc");
+        let options = read::Options::new().max_elements(Some(2));
+        assert_eq!(options.byte_values(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+
+        // The default limit applies without options too.
+        let mut buf = vec![];
+        write::unsigned(&mut buf, read::DEFAULT_MAX_ELEMENTS as u64 + 1)
+            .expect("Should write number");
+        assert_eq!(read::byte_values(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+        buf.push(0);
+        assert_eq!(read::ragged_unsigned(&mut &buf[..], None).unwrap_err().kind(),
+                   read::ErrorKind::LimitExceeded);
+
+        let options = read::Options::new().max_elements(None);
+        assert_eq!(options.byte_values(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_length_prefixed() {
+        let mut buf = vec![];
+        assert_eq!(write::length_prefixed(&mut buf, b"abc").expect("Should write bytes"),
+                   4);
+        assert_eq!(buf, [3, b'a', b'b', b'c']);
+
+        let payload: Vec<u8> = (0..20000u32).map(|i| i as u8).collect();
+        let mut buf = vec![];
+        assert_eq!(write::length_prefixed_from_reader(&mut buf, &mut &payload[..], 20000)
+                       .expect("Should write bytes"),
+                   20003);
+
         let mut readable = &buf[..];
-        match read::unsigned(&mut readable) {
-            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 20000);
+        assert_eq!(readable, &payload[..]);
+    }
+
+    #[test]
+    fn test_length_prefixed_from_short_reader() {
+        let mut buf = vec![];
+        match write::length_prefixed_from_reader(&mut buf, &mut &b"ab"[..], 3) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
             otherwise => panic!("Unexpected: {:?}", otherwise),
         }
     }
 
     #[test]
-    fn test_read_signed_not_enough_data() {
-        let buf = [CONTINUATION_BIT];
+    fn test_length_footer() {
+        for len in &[0usize, 1, 127, 128, 300] {
+            // Bodies that end in continuation bytes must still be found.
+            for &fill in &[0x01u8, 0x80, 0xff] {
+                let body = vec![fill; *len];
+                let mut buf = vec![];
+                let written = write::with_length_footer(&mut buf, |b| {
+                        b.extend_from_slice(&body);
+                        Ok(())
+                    })
+                    .expect("Should write body");
+                assert_eq!(written, buf.len());
+                assert_eq!(written, len + write::unsigned_len(*len as u64));
+                assert_eq!(read::from_length_footer(&buf).expect("Should find body"),
+                           &body[..]);
+            }
+        }
+
+        let mut buf = vec![];
+        let written = write::with_length_footer(&mut buf, |b| {
+                write::unsigned(b, 12857)?;
+                write::signed(b, -1)?;
+                Ok(())
+            })
+            .expect("Should write body");
+        assert_eq!(written, 4);
+        let mut body = read::from_length_footer(&buf).expect("Should find body");
+        assert_eq!(read::unsigned(&mut body).expect("Should read number"), 12857);
+        assert_eq!(read::signed(&mut body).expect("Should read number"), -1);
+
+        match write::with_length_footer(&mut vec![], |_| Err(io::ErrorKind::Other.into())) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::Other),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        for &(buf, kind) in &[(&[][..], io::ErrorKind::UnexpectedEof),
+                              (&[1, 0x80], io::ErrorKind::UnexpectedEof),
+                              (&[1, 2, 3, 0x02], io::ErrorKind::InvalidData),
+                              (&[0x05], io::ErrorKind::InvalidData)] {
+            match read::from_length_footer(buf) {
+                Err(read::Error::IoError(e)) => assert_eq!(e.kind(), kind),
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_multiple() {
+        let buf = [2u8 | CONTINUATION_BIT, 1u8, 1u8];
+
         let mut readable = &buf[..];
-        match read::signed(&mut readable) {
+        assert_eq!(read::unsigned(&mut readable).expect("Should read first number"),
+                   130u64);
+        assert_eq!(read::unsigned(&mut readable).expect("Should read first number"),
+                   1u64);
+    }
+
+    #[test]
+    fn test_boundaries() {
+        let buf = [2u8 | CONTINUATION_BIT, 1, 1, CONTINUATION_BIT, CONTINUATION_BIT, 0x7f];
+        assert_eq!(read::boundaries(&buf).expect("Should scan boundaries"),
+                   vec![0, 2, 3]);
+
+        assert_eq!(read::boundaries(&[]).expect("Should scan boundaries"),
+                   Vec::<usize>::new());
+
+        match read::boundaries(&[1, CONTINUATION_BIT]) {
             Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
             otherwise => panic!("Unexpected: {:?}", otherwise),
         }
     }
 
     #[test]
-    fn test_write_unsigned_not_enough_space() {
-        let mut buf = [0; 1];
-        let mut writable = &mut buf[..];
-        match write::unsigned(&mut writable, 128) {
-            Err(e) => assert_eq!(e.kind(), io::ErrorKind::WriteZero),
+    fn test_searchable_table() {
+        let vals = [0u64, 3, 127, 128, 12857, 624485, u64::MAX];
+        let mut buf = vec![];
+        for &val in &vals {
+            write::unsigned(&mut buf, val).expect("Should write number");
+        }
+
+        let table = read::SearchableTable::new(&buf).expect("Should index table");
+        assert_eq!(table.len(), vals.len());
+        for (i, &val) in vals.iter().enumerate() {
+            assert_eq!(table.get(i).expect("Should read number"), val);
+            assert_eq!(table.binary_search(val).expect("Should search"), Ok(i));
+        }
+        for &(target, expected) in &[(1, 1), (126, 2), (129, 4), (u64::MAX - 1, 6)] {
+            assert_eq!(table.binary_search(target).expect("Should search"),
+                       Err(expected));
+        }
+
+        let table = read::SearchableTable::new(&[]).expect("Should index table");
+        assert!(table.is_empty());
+        assert_eq!(table.binary_search(7).expect("Should search"), Err(0));
+
+        // Only probed numbers are decoded, so an overflow is reported when hit.
+        let buf = [1, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x7f];
+        let table = read::SearchableTable::new(&buf).expect("Should index table");
+        assert_eq!(table.get(0).expect("Should read number"), 1);
+        assert_eq!(table.binary_search(1).unwrap_err().kind(), read::ErrorKind::Overflow);
+
+        assert_eq!(read::SearchableTable::new(&[CONTINUATION_BIT]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_read_instrumented() {
+        let mut buf = vec![];
+        for &val in &[1u64, 127, 128, 12857, 624485] {
+            write::unsigned(&mut buf, val).expect("Should write number");
+        }
+        write::signed(&mut buf, -1).expect("Should write number");
+
+        let mut readable = read::Instrumented::new(&buf[..]);
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 1);
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 127);
+        assert_eq!(readable.stats(),
+                   read::DecodeStats { values: 2, bytes: 2, single_byte_values: 2 });
+
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 128);
+        readable.reset_stats();
+        assert_eq!(readable.stats(), read::DecodeStats::default());
+
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 12857);
+        assert_eq!(read::unsigned(&mut readable).expect("Should read number"), 624485);
+        assert_eq!(read::signed(&mut readable).expect("Should read number"), -1);
+        assert_eq!(readable.stats(),
+                   read::DecodeStats { values: 3, bytes: 6, single_byte_values: 1 });
+        assert!(readable.into_inner().is_empty());
+    }
+
+    #[test]
+    fn test_expect_unsigned() {
+        let buf = [2u8 | CONTINUATION_BIT, 1, 7];
+        let mut readable = &buf[..];
+        read::expect_unsigned(&mut readable, 130).expect("Should match number");
+        match read::expect_unsigned(&mut readable, 8) {
+            Err(read::Error::Mismatch { expected: 8, found: 7 }) => {}
             otherwise => panic!("Unexpected: {:?}", otherwise),
         }
+        assert_eq!(read::expect_unsigned(&mut readable, 0).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
     }
 
     #[test]
-    fn test_write_signed_not_enough_space() {
-        let mut buf = [0; 1];
-        let mut writable = &mut buf[..];
-        match write::signed(&mut writable, 128) {
-            Err(e) => assert_eq!(e.kind(), io::ErrorKind::WriteZero),
+    fn test_read_unsigned_then() {
+        let buf = [2u8 | CONTINUATION_BIT, 1];
+        assert_eq!(read::unsigned_then(&mut &buf[..], |i| Ok(i + 1))
+                       .expect("Should read number"),
+                   131);
+
+        match read::unsigned_then(&mut &buf[..], |found| {
+            Err::<(), _>(read::Error::Mismatch { expected: 0, found })
+        }) {
+            Err(read::Error::Mismatch { expected: 0, found: 130 }) => {}
             otherwise => panic!("Unexpected: {:?}", otherwise),
         }
+
+        let mut called = false;
+        assert!(read::unsigned_then(&mut &[CONTINUATION_BIT][..], |i| {
+                called = true;
+                Ok(i)
+            })
+            .is_err());
+        assert!(!called);
     }
 
     #[test]
-    fn dogfood_signed() {
-        fn inner(i: i64) {
-            let mut buf = [0u8; 1024];
+    fn test_char() {
+        for &c in &['\0', 'a', '\u{7f}', '\u{80}', '\u{d7ff}', '\u{e000}', '\u{10ffff}'] {
+            let mut buf = vec![];
+            write::char(&mut buf, c).expect("Should write char");
+            assert_eq!(read::char(&mut &buf[..]).expect("Should read char"), c);
+        }
 
-            {
-                let mut writable = &mut buf[..];
-                write::signed(&mut writable, i).expect("Should write signed number");
+        for &val in &[0xd800u64, 0xdfff, 0x110000, u32::MAX as u64 + 0x61] {
+            let mut buf = vec![];
+            write::unsigned(&mut buf, val).expect("Should write number");
+            match read::char(&mut &buf[..]) {
+                Err(read::Error::InvalidChar(v)) => assert_eq!(v, val),
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_discriminant() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 1).expect("Should write number");
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+
+        let mut readable = &buf[..];
+        let tag = |val| if val < 3 { Some(val as u8) } else { None };
+        assert_eq!(read::discriminant(&mut readable, tag).expect("Should read tag"), 1);
+        match read::discriminant(&mut readable, tag) {
+            Err(e @ read::Error::UnknownDiscriminant(12857)) => {
+                assert_eq!(e.kind(), read::ErrorKind::UnknownDiscriminant)
             }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert!(readable.is_empty());
+    }
 
+    #[test]
+    fn test_checksummed() {
+        let mut buf = vec![];
+        assert_eq!(write::unsigned_checksummed(&mut buf, 12857).expect("Should write number"),
+                   3);
+        assert_eq!(buf, [57 | CONTINUATION_BIT, 100, (57 | CONTINUATION_BIT) ^ 100]);
+        assert_eq!(read::unsigned_checksummed(&mut &buf[..]).expect("Should read number"),
+                   12857);
+
+        for &i in &[0u64, 1, 128, u64::MAX] {
+            let mut buf = vec![];
+            write::unsigned_checksummed(&mut buf, i).expect("Should write number");
             let mut readable = &buf[..];
-            let result = read::signed(&mut readable).expect("Should be able to read it back again");
-            assert_eq!(i, result);
+            assert_eq!(read::unsigned_checksummed(&mut readable).expect("Should read number"),
+                       i);
+            assert!(readable.is_empty());
         }
-        for i in -513..513 {
-            inner(i);
+
+        // A flipped bit in the number.
+        buf[1] ^= 0x04;
+        match read::unsigned_checksummed(&mut &buf[..]) {
+            Err(e @ read::Error::ChecksumMismatch { .. }) => {
+                assert_eq!(e.kind(), read::ErrorKind::ChecksumMismatch)
+            }
+            otherwise => panic!("Unexpected: {:?}", otherwise),
         }
-        inner(std::i64::MIN);
+        assert_eq!(read::unsigned_checksummed(&mut &buf[..2]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
     }
 
     #[test]
-    fn dogfood_unsigned() {
-        for i in 0..1025 {
-            let mut buf = [0u8; 1024];
+    fn test_read_tee() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+        write::signed(&mut buf, -65).expect("Should write number");
+        buf.push(0xff);
 
-            {
-                let mut writable = &mut buf[..];
-                write::unsigned(&mut writable, i).expect("Should write signed number");
+        let mut readable = &buf[..];
+        let mut forwarded = vec![];
+        assert_eq!(read::unsigned_tee(&mut readable, &mut forwarded)
+                       .expect("Should read number"),
+                   12857);
+        assert_eq!(read::signed_tee(&mut readable, &mut forwarded).expect("Should read number"),
+                   -65);
+        assert_eq!(forwarded, &buf[..4]);
+        assert_eq!(readable, [0xff]);
+
+        let mut buf = [0xffu8; 12];
+        buf[9] = 0x02;
+        let mut readable = &buf[..];
+        let mut forwarded = vec![];
+        assert_eq!(read::unsigned_tee(&mut readable, &mut forwarded).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(forwarded, &buf[..10]);
+        assert_eq!(readable.len(), 2);
+    }
+
+    #[test]
+    fn test_read_unsigned_span() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 12857).expect("Should write number");
+        write::unsigned_min_bytes(&mut buf, 1, 3).expect("Should write number");
+        write::unsigned(&mut buf, 0).expect("Should write number");
+
+        let mut readable = &buf[..];
+        let mut offset = 100;
+        for &(val, ref span) in &[(12857, 100..102), (1, 102..105), (0, 105..106)] {
+            let (found, found_span) = read::unsigned_span(&mut readable, offset)
+                .expect("Should read number");
+            assert_eq!((found, &found_span), (val, span));
+            offset = found_span.end;
+        }
+
+        assert_eq!(read::unsigned_span(&mut &[0x80][..], 0).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_unsigned_hex() {
+        for &i in &[0u64, 1, 127, 128, 624485, u64::MAX] {
+            let mut s = String::new();
+            write::unsigned_hex(&mut s, i).expect("Should write number");
+
+            let mut buf = vec![];
+            write::unsigned(&mut buf, i).expect("Should write number");
+            assert_eq!(s.len(), 2 * buf.len());
+            assert_eq!(read::unsigned_hex(&s).expect("Should read number"), i);
+            assert_eq!(read::unsigned_hex(&s.to_uppercase()).expect("Should read number"), i);
+        }
+
+        for s in &["", "80"] {
+            assert_eq!(read::unsigned_hex(s).unwrap_err().kind(),
+                       read::ErrorKind::UnexpectedEndOfData);
+        }
+        for &(s, kind) in &[("8", io::ErrorKind::InvalidInput),
+                            ("0g", io::ErrorKind::InvalidInput),
+                            ("+1", io::ErrorKind::InvalidInput),
+                            ("é0", io::ErrorKind::InvalidInput),
+                            ("0202", io::ErrorKind::InvalidData)] {
+            match read::unsigned_hex(s) {
+                Err(read::Error::IoError(e)) => assert_eq!(e.kind(), kind),
+                otherwise => panic!("Unexpected: {:?}", otherwise),
             }
+        }
+    }
 
-            let mut readable = &buf[..];
-            let result = read::unsigned(&mut readable)
-                .expect("Should be able to read it back again");
-            assert_eq!(i, result);
+    #[test]
+    fn test_read_u8() {
+        let mut buf = vec![];
+        for &i in &[0u64, 127, 128, 200, 255, 256, u64::MAX] {
+            write::unsigned(&mut buf, i).expect("Should write number");
+        }
+        let mut readable = &buf[..];
+        for &i in &[0u8, 127, 128, 200, 255] {
+            assert_eq!(read::u8(&mut readable).expect("Should read number"), i);
         }
+        assert_eq!(read::u8(&mut readable).unwrap_err().kind(), read::ErrorKind::Overflow);
+        assert_eq!(read::u8(&mut readable).unwrap_err().kind(), read::ErrorKind::Overflow);
+        assert!(readable.is_empty());
     }
 
     #[test]
-    fn test_read_unsigned_overflow() {
-        let buf = [2u8 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   1];
+    fn test_u24_u48() {
+        let mut buf = vec![];
+        assert_eq!(write::unsigned_u24(&mut buf, (1 << 24) - 1).expect("Should write number"),
+                   4);
+        match write::unsigned_u24(&mut buf, 1 << 24) {
+            Err(write::Error::OutOfRange) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(write::unsigned_u48(&mut buf, (1 << 48) - 1).expect("Should write number"),
+                   7);
+        match write::unsigned_u48(&mut buf, 1 << 48) {
+            Err(write::Error::OutOfRange) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        write::unsigned(&mut buf, 1 << 24).expect("Should write number");
+        write::unsigned(&mut buf, 1 << 48).expect("Should write number");
+
         let mut readable = &buf[..];
-        assert!(read::unsigned(&mut readable).is_err());
+        assert_eq!(read::unsigned_u24(&mut readable).expect("Should read number"),
+                   (1 << 24) - 1);
+        assert_eq!(read::unsigned_u48(&mut readable).expect("Should read number"),
+                   (1 << 48) - 1);
+        assert_eq!(read::unsigned_u24(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(read::unsigned_u48(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert!(readable.is_empty());
     }
 
     #[test]
-    fn test_read_signed_overflow() {
-        let buf = [2u8 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   2 | CONTINUATION_BIT,
-                   1];
+    fn test_read_as() {
+        let mut buf = vec![];
+        write::unsigned(&mut buf, 255).expect("Should write number");
+        write::unsigned(&mut buf, 256).expect("Should write number");
+        write::unsigned(&mut buf, u64::MAX).expect("Should write number");
         let mut readable = &buf[..];
-        assert!(read::signed(&mut readable).is_err());
+        assert_eq!(read::unsigned_as::<u8, _>(&mut readable).expect("Should read number"),
+                   255);
+        assert_eq!(read::unsigned_as::<u8, _>(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(read::unsigned_as::<i64, _>(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+
+        let mut buf = vec![];
+        write::signed(&mut buf, -128).expect("Should write number");
+        write::signed(&mut buf, -129).expect("Should write number");
+        write::signed(&mut buf, -1).expect("Should write number");
+        let mut readable = &buf[..];
+        assert_eq!(read::signed_as::<i8, _>(&mut readable).expect("Should read number"),
+                   -128);
+        assert_eq!(read::signed_as::<i8, _>(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+        assert_eq!(read::signed_as::<u64, _>(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
     }
 
     #[test]
-    fn test_read_multiple() {
-        let buf = [2u8 | CONTINUATION_BIT, 1u8, 1u8];
+    fn test_read_nonzero() {
+        assert_eq!(read::nonzero_u64(&mut &[1u8][..]).expect("Should read number").get(),
+                   1);
+        assert_eq!(read::nonzero_u32(&mut &[CONTINUATION_BIT, 1][..])
+                       .expect("Should read number")
+                       .get(),
+                   128);
+        assert_eq!(read::nonzero_u64(&mut &[0u8][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedZero);
+        assert_eq!(read::nonzero_u32(&mut &[CONTINUATION_BIT, 0][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedZero);
+
+        let mut buf = vec![];
+        write::unsigned(&mut buf, u32::MAX as u64 + 1).expect("Should write number");
+        assert_eq!(read::nonzero_u64(&mut &buf[..]).expect("Should read number").get(),
+                   u32::MAX as u64 + 1);
+        assert_eq!(read::nonzero_u32(&mut &buf[..]).unwrap_err().kind(),
+                   read::ErrorKind::Overflow);
+    }
+
+    #[test]
+    fn test_has_complete_value() {
+        assert!(!read::has_complete_value(&[]));
+        assert!(read::has_complete_value(&[0]));
+        assert!(read::has_complete_value(&[CONTINUATION_BIT, 1, CONTINUATION_BIT]));
+        assert!(!read::has_complete_value(&[CONTINUATION_BIT, CONTINUATION_BIT]));
+
+        let mut buf = [CONTINUATION_BIT; 11];
+        buf[9] = 1;
+        assert!(read::has_complete_value(&buf));
+        buf[9] = CONTINUATION_BIT;
+        buf[10] = 1;
+        assert!(!read::has_complete_value(&buf));
+    }
+
+    #[test]
+    fn test_unsigned_eq_bytes() {
+        let eq = |a: &[u8], b: &[u8]| read::unsigned_eq_bytes(a, b).expect("Should compare");
+
+        assert!(eq(&[2], &[2]));
+        assert!(!eq(&[2], &[3]));
+        assert!(eq(&[2], &[2 | CONTINUATION_BIT, CONTINUATION_BIT, 0]));
+        assert!(eq(&[2 | CONTINUATION_BIT, 0, 0xff], &[2, 0xff]));
+        assert!(!eq(&[2 | CONTINUATION_BIT, 1], &[2]));
+
+        // Both numbers are larger than a u64.
+        let mut big = [0xffu8; 12];
+        big[11] = 1;
+        let mut padded = [0xffu8; 13];
+        padded[11] = CONTINUATION_BIT | 1;
+        padded[12] = 0;
+        assert!(eq(&big, &padded));
+        big[0] = 0xfe;
+        assert!(!eq(&big, &padded));
+
+        assert_eq!(read::unsigned_eq_bytes(&[CONTINUATION_BIT], &[0]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(read::unsigned_eq_bytes(&[0], &[]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+
+    #[test]
+    fn test_tagged_unsigned() {
+        let mut buf = vec![];
+        assert_eq!(write::tagged_unsigned(&mut buf, 1, 0, 150)
+                       .expect("Should write tagged number"),
+                   3);
+        assert_eq!(buf, [0x08, 0x96, 0x01]);
+
+        write::tagged_unsigned(&mut buf, u32::MAX, 7, u64::MAX)
+            .expect("Should write tagged number");
 
         let mut readable = &buf[..];
-        assert_eq!(read::unsigned(&mut readable).expect("Should read first number"),
-                   130u64);
-        assert_eq!(read::unsigned(&mut readable).expect("Should read first number"),
-                   1u64);
+        assert_eq!(read::tagged_unsigned(&mut readable).expect("Should read tagged number"),
+                   (1, 0, 150));
+        assert_eq!(read::tagged_unsigned(&mut readable).expect("Should read tagged number"),
+                   (u32::MAX, 7, u64::MAX));
+    }
+
+    #[test]
+    fn test_tagged_unsigned_invalid() {
+        let mut buf = vec![];
+        match write::tagged_unsigned(&mut buf, 1, 8, 0) {
+            Err(e) => assert_eq!(e.kind(), io::ErrorKind::InvalidInput),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert!(buf.is_empty());
+
+        write::unsigned(&mut buf, (u32::MAX as u64 + 1) << 3).expect("Should write number");
+        write::unsigned(&mut buf, 0).expect("Should write number");
+        match read::tagged_unsigned(&mut &buf[..]) {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+
+    #[test]
+    fn test_read_unsigned_with_bytes() {
+        let buf = [2u8 | CONTINUATION_BIT, 1, CONTINUATION_BIT, CONTINUATION_BIT, 0, 7];
+        let mut readable = &buf[..];
+        assert_eq!(read::unsigned_with_bytes(&mut readable).expect("Should read number"),
+                   (130, vec![2 | CONTINUATION_BIT, 1]));
+        assert_eq!(read::unsigned_with_bytes(&mut readable).expect("Should read number"),
+                   (0, vec![CONTINUATION_BIT, CONTINUATION_BIT, 0]));
+        assert_eq!(readable, [7]);
+    }
+
+    #[test]
+    fn test_read_signed_with_width() {
+        let buf = [0x7eu8, 0xfe, 0xff, 0x7f, 0x80, 0x7f];
+        let mut readable = &buf[..];
+        assert_eq!(read::signed_with_width(&mut readable).expect("Should read number"),
+                   (-2, 7));
+        assert_eq!(read::signed_with_width(&mut readable).expect("Should read number"),
+                   (-2, 21));
+        assert_eq!(read::signed_with_width(&mut readable).expect("Should read number"),
+                   (-128, 14));
+
+        let mut out = vec![];
+        write::signed_fixed_width(&mut out, -2, 21 / 7).expect("Should write number");
+        assert_eq!(out, &buf[1..4]);
+    }
+
+    #[test]
+    fn test_read_unsigned_at() {
+        let mut cursor = io::Cursor::new(vec![2u8 | CONTINUATION_BIT, 1, 7, CONTINUATION_BIT]);
+        assert_eq!(read::unsigned_at(&mut cursor).expect("Should read number"), 130);
+        assert_eq!(cursor.position(), 2);
+        assert_eq!(read::unsigned_at(&mut cursor).expect("Should read number"), 7);
+        assert_eq!(cursor.position(), 3);
+
+        match read::unsigned_at(&mut cursor) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(cursor.position(), 4);
+
+        cursor.set_position(10);
+        assert!(read::unsigned_at(&mut cursor).is_err());
+        assert_eq!(cursor.position(), 10);
     }
 }
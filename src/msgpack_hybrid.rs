@@ -0,0 +1,105 @@
+//! A hybrid of MessagePack positive fixints and LEB128, for unsigned numbers.
+//!
+//! A number below `0x80` is written as a single byte holding its value, just
+//! like a MessagePack positive fixint, which also makes it a one byte LEB128
+//! number. Any larger number is written as the `ESCAPE` byte, `0xff`, followed
+//! by its unsigned LEB128 encoding. Every other first byte is invalid.
+//!
+//! Note that `0xff` is also the MessagePack negative fixint for `-1`, which
+//! this format takes as the escape. Only `0xe0..=0xfe` of the negative fixint
+//! range, along with `0x80..=0xdf`, are left for other uses.
+//!
+//! ```
+//! let mut buf = vec![];
+//! leb128::msgpack_hybrid::write_unsigned(&mut buf, 5).unwrap();
+//! leb128::msgpack_hybrid::write_unsigned(&mut buf, 300).unwrap();
+//! assert_eq!(buf, [0x05, 0xff, 0xac, 0x02]);
+//!
+//! let mut readable = &buf[..];
+//! assert_eq!(leb128::msgpack_hybrid::read_unsigned(&mut readable).unwrap(), 5);
+//! assert_eq!(leb128::msgpack_hybrid::read_unsigned(&mut readable).unwrap(), 300);
+//! ```
+
+use read;
+use std::io;
+use write;
+
+/// The byte that introduces a LEB128 encoded number.
+pub const ESCAPE: u8 = 0xff;
+
+/// Read an unsigned number in the hybrid encoding from the given
+/// `std::io::Read`able and return it, or an error if reading failed. A first
+/// byte that is neither a fixint nor `ESCAPE` is an `InvalidData` IO error.
+pub fn read_unsigned<R>(r: &mut R) -> Result<u64, read::Error>
+    where R: ?Sized + io::Read
+{
+    let mut buf = [0];
+    r.read_exact(&mut buf)?;
+    match buf[0] {
+        byte @ 0x00..=0x7f => Ok(byte as u64),
+        ESCAPE => read::unsigned(r),
+        byte => {
+            Err(read::Error::IoError(io::Error::new(io::ErrorKind::InvalidData,
+                                                    format!("invalid first byte {:#04x}",
+                                                            byte))))
+        }
+    }
+}
+
+/// Write the given unsigned number in the hybrid encoding to the given
+/// `std::io::Write`able. Returns the number of bytes written to `w`, or an
+/// error if writing failed.
+pub fn write_unsigned<W>(w: &mut W, val: u64) -> Result<usize, io::Error>
+    where W: ?Sized + io::Write
+{
+    if val < 0x80 {
+        w.write_all(&[val as u8])?;
+        return Ok(1);
+    }
+    w.write_all(&[ESCAPE])?;
+    Ok(1 + write::unsigned(w, val)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixint() {
+        for val in 0..0x80u64 {
+            let mut buf = vec![];
+            assert_eq!(write_unsigned(&mut buf, val).expect("Should write number"), 1);
+            assert_eq!(buf, [val as u8]);
+            assert_eq!(read_unsigned(&mut &buf[..]).expect("Should read number"), val);
+        }
+    }
+
+    #[test]
+    fn test_escaped() {
+        for &val in &[0x80u64, 0xff, 12857, u64::MAX] {
+            let mut buf = vec![];
+            let len = write_unsigned(&mut buf, val).expect("Should write number");
+            assert_eq!(len, 1 + write::unsigned_len(val));
+            assert_eq!(buf[0], ESCAPE);
+            assert_eq!(read_unsigned(&mut &buf[..]).expect("Should read number"), val);
+        }
+
+        // An escaped small number is accepted even though it is never written.
+        assert_eq!(read_unsigned(&mut &[ESCAPE, 0x05][..]).expect("Should read number"), 5);
+    }
+
+    #[test]
+    fn test_invalid() {
+        for &byte in &[0x80u8, 0xc0, 0xe0, 0xfe] {
+            match read_unsigned(&mut &[byte, 0x01][..]) {
+                Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::InvalidData),
+                otherwise => panic!("Unexpected: {:?}", otherwise),
+            }
+        }
+
+        assert_eq!(read_unsigned(&mut &[][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(read_unsigned(&mut &[ESCAPE][..]).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+    }
+}
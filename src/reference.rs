@@ -0,0 +1,96 @@
+//! Deliberately naive reference encoders, for use as test oracles.
+//!
+//! These follow the DWARF 4 standard's appendix C pseudocode as literally as
+//! possible and share no code with the `write` module, so that the optimized
+//! encoders of this crate, or of any other crate, can be checked against them.
+//! They favor obviousness over speed and should not be used for anything
+//! else.
+
+/// Encode the given unsigned number as LEB128, one byte at a time.
+pub fn encode_unsigned(val: u64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut value = val;
+
+    loop {
+        // Take the low order 7 bits of the value.
+        let mut byte = (value % 128) as u8;
+
+        // Drop those 7 bits from the value.
+        value /= 128;
+
+        // If there is anything left, more bytes follow, which is signaled by
+        // setting the high order bit of this byte.
+        if value != 0 {
+            byte += 128;
+        }
+
+        bytes.push(byte);
+
+        if value == 0 {
+            return bytes;
+        }
+    }
+}
+
+/// Encode the given signed number as LEB128, one byte at a time.
+pub fn encode_signed(val: i64) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    let mut value = val;
+
+    loop {
+        // Take the low order 7 bits of the value. Euclidean remainder keeps
+        // this in 0..128 even when the value is negative.
+        let mut byte = value.rem_euclid(128) as u8;
+
+        // Drop those 7 bits from the value, rounding towards negative
+        // infinity, which is an arithmetic shift right by 7.
+        value = value.div_euclid(128);
+
+        // The value is complete once only sign bits remain and the sign bit of
+        // this byte (bit 6) agrees with them, so that a decoder sign extends
+        // to the right value.
+        let sign_bit_set = byte & 64 != 0;
+        let done = (value == 0 && !sign_bit_set) || (value == -1 && sign_bit_set);
+
+        // Otherwise more bytes follow, which is signaled by setting the high
+        // order bit of this byte.
+        if !done {
+            byte += 128;
+        }
+
+        bytes.push(byte);
+
+        if done {
+            return bytes;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Examples from the DWARF 4 standard, section 7.6, figures 22 and 23.
+    #[test]
+    fn test_encode_unsigned() {
+        assert_eq!(encode_unsigned(2), [2]);
+        assert_eq!(encode_unsigned(127), [127]);
+        assert_eq!(encode_unsigned(128), [0x80, 1]);
+        assert_eq!(encode_unsigned(129), [0x81, 1]);
+        assert_eq!(encode_unsigned(130), [0x82, 1]);
+        assert_eq!(encode_unsigned(12857), [0xb9, 100]);
+    }
+
+    #[test]
+    fn test_encode_signed() {
+        assert_eq!(encode_signed(2), [2]);
+        assert_eq!(encode_signed(-2), [0x7e]);
+        assert_eq!(encode_signed(127), [0xff, 0]);
+        assert_eq!(encode_signed(-127), [0x81, 0x7f]);
+        assert_eq!(encode_signed(128), [0x80, 1]);
+        assert_eq!(encode_signed(-128), [0x80, 0x7f]);
+        assert_eq!(encode_signed(129), [0x81, 1]);
+        assert_eq!(encode_signed(-129), [0xff, 0x7e]);
+        assert_eq!(encode_signed(i64::MIN).len(), 10);
+    }
+}
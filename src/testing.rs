@@ -0,0 +1,208 @@
+//! Helpers for testing code that reads or writes LEB128 numbers.
+//!
+//! ```
+//! use leb128::testing::ChunkedReader;
+//!
+//! // One byte at a time.
+//! let mut readable = ChunkedReader::new(&[0xe5, 0x8e, 0x26], &[1, 1, 1]);
+//! assert_eq!(leb128::read::unsigned(&mut readable).unwrap(), 624485);
+//! ```
+
+use read;
+use std::io;
+use write;
+
+/// A `std::io::Read`able that returns a byte slice in chunks of the given
+/// sizes, to exercise readers against partial reads.
+///
+/// Each call to `read` returns at most the rest of the current chunk. A chunk
+/// size of `0` makes one call return `Ok(0)`. Once the chunk sizes run out,
+/// the rest of the data is returned as fast as the caller's buffers allow.
+#[derive(Clone, Debug)]
+pub struct ChunkedReader<'a> {
+    data: &'a [u8],
+    chunks: &'a [usize],
+    // What is left of the current chunk, if it was only partially returned.
+    pending: Option<usize>,
+}
+
+impl<'a> ChunkedReader<'a> {
+    /// Create a reader over `data` that returns it in chunks of the sizes in
+    /// `chunks`, in order.
+    pub fn new(data: &'a [u8], chunks: &'a [usize]) -> ChunkedReader<'a> {
+        ChunkedReader {
+            data,
+            chunks,
+            pending: None,
+        }
+    }
+
+    /// Return the data that has not been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data
+    }
+
+    fn next_chunk(&mut self) -> Option<usize> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+        let (&first, rest) = self.chunks.split_first()?;
+        self.chunks = rest;
+        Some(first)
+    }
+}
+
+impl<'a> io::Read for ChunkedReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let chunk = match self.next_chunk() {
+            Some(0) => return Ok(0),
+            Some(chunk) => chunk,
+            None => self.data.len(),
+        };
+
+        let n = chunk.min(buf.len()).min(self.data.len());
+        if chunk > n && n < self.data.len() {
+            self.pending = Some(chunk - n);
+        }
+        buf[..n].copy_from_slice(&self.data[..n]);
+        self.data = &self.data[n..];
+        Ok(n)
+    }
+}
+
+/// Encode `val` as unsigned LEB128 and decode it back, panicking with a
+/// description of the mismatch if the result differs or is not canonical.
+pub fn assert_roundtrip_unsigned(val: u64) {
+    let mut buf = vec![];
+    write::unsigned(&mut buf, val).expect("writing to a Vec cannot fail");
+    match read::unsigned(&mut &buf[..]) {
+        Ok(decoded) if decoded == val => assert_canonical(&buf),
+        otherwise => {
+            panic!("unsigned {} encoded as {:02x?} decoded as {:?}", val, buf, otherwise)
+        }
+    }
+}
+
+/// Encode `val` as signed LEB128 and decode it back, panicking with a
+/// description of the mismatch if the result differs or is not canonical.
+pub fn assert_roundtrip_signed(val: i64) {
+    let mut buf = vec![];
+    write::signed(&mut buf, val).expect("writing to a Vec cannot fail");
+    match read::signed(&mut &buf[..]) {
+        Ok(decoded) if decoded == val => assert_canonical_signed(&buf),
+        otherwise => {
+            panic!("signed {} encoded as {:02x?} decoded as {:?}", val, buf, otherwise)
+        }
+    }
+}
+
+/// Panic unless `buf` holds exactly one unsigned LEB128 number, encoded in as
+/// few bytes as possible.
+pub fn assert_canonical(buf: &[u8]) {
+    let mut readable = buf;
+    match read::Options::new().strict(true).unsigned(&mut readable) {
+        Ok(_) if readable.is_empty() => {}
+        Ok(_) => panic!("{:02x?} has {} bytes after the number", buf, readable.len()),
+        Err(e) => panic!("{:02x?} is not a canonical unsigned number: {}", buf, e),
+    }
+}
+
+/// Panic unless `buf` holds exactly one signed LEB128 number, encoded in as
+/// few bytes as possible.
+pub fn assert_canonical_signed(buf: &[u8]) {
+    let mut readable = buf;
+    match read::Options::new().strict(true).signed(&mut readable) {
+        Ok(_) if readable.is_empty() => {}
+        Ok(_) => panic!("{:02x?} has {} bytes after the number", buf, readable.len()),
+        Err(e) => panic!("{:02x?} is not a canonical signed number: {}", buf, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    #[test]
+    fn test_chunked_reader_chunks() {
+        let data = [1, 2, 3, 4, 5, 6];
+        let mut readable = ChunkedReader::new(&data, &[2, 0, 3]);
+        let mut buf = [0; 8];
+
+        assert_eq!(readable.read(&mut buf).expect("Should read"), 2);
+        assert_eq!(buf[..2], [1, 2]);
+        assert_eq!(readable.read(&mut buf).expect("Should read"), 0);
+
+        // A chunk larger than the buffer is returned over several reads.
+        assert_eq!(readable.read(&mut buf[..2]).expect("Should read"), 2);
+        assert_eq!(buf[..2], [3, 4]);
+        assert_eq!(readable.read(&mut buf).expect("Should read"), 1);
+        assert_eq!(buf[..1], [5]);
+
+        // Out of chunk sizes, so the rest comes at once.
+        assert_eq!(readable.remaining(), [6]);
+        assert_eq!(readable.read(&mut buf).expect("Should read"), 1);
+        assert_eq!(buf[..1], [6]);
+        assert_eq!(readable.read(&mut buf).expect("Should read"), 0);
+    }
+
+    #[test]
+    fn test_chunked_reader_numbers() {
+        let mut data = vec![];
+        for &val in &[0u64, 127, 128, 624485, u64::MAX] {
+            write::unsigned(&mut data, val).expect("Should write number");
+        }
+
+        for chunks in &[&[1usize; 32][..], &[3, 1, 4, 1, 5, 9, 2, 6][..], &[][..]] {
+            let mut readable = ChunkedReader::new(&data, chunks);
+            for &val in &[0u64, 127, 128, 624485, u64::MAX] {
+                assert_eq!(read::unsigned(&mut readable).expect("Should read number"), val);
+            }
+            assert!(readable.remaining().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_chunked_reader_zero_is_end_of_input() {
+        let mut readable = ChunkedReader::new(&[0xe5, 0x8e, 0x26], &[1, 0]);
+        assert_eq!(read::unsigned(&mut readable).unwrap_err().kind(),
+                   read::ErrorKind::UnexpectedEndOfData);
+        assert_eq!(readable.remaining(), [0x8e, 0x26]);
+    }
+
+    #[test]
+    fn test_assert_roundtrip() {
+        for &val in &[0u64, 1, 127, 128, 12857, u64::MAX] {
+            assert_roundtrip_unsigned(val);
+        }
+        for &val in &[0i64, -1, 63, -64, 64, -65, i64::MIN, i64::MAX] {
+            assert_roundtrip_signed(val);
+        }
+    }
+
+    #[test]
+    fn test_assert_canonical() {
+        assert_canonical(&[0x00]);
+        assert_canonical(&[0x80, 0x01]);
+        assert_canonical_signed(&[0x40]);
+        assert_canonical_signed(&[0xc0, 0x00]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a canonical unsigned number")]
+    fn test_assert_canonical_overlong() {
+        assert_canonical(&[0x81, 0x00]);
+    }
+
+    #[test]
+    #[should_panic(expected = "not a canonical signed number")]
+    fn test_assert_canonical_signed_overlong() {
+        assert_canonical_signed(&[0xff, 0x7f]);
+    }
+
+    #[test]
+    #[should_panic(expected = "bytes after the number")]
+    fn test_assert_canonical_trailing() {
+        assert_canonical(&[0x01, 0x02]);
+    }
+}
@@ -0,0 +1,143 @@
+//! Bulk conversion between LEB128 flavors.
+
+use read;
+use std::io;
+use write;
+
+/// Read `count` signed LEB128 numbers from `r` and write each of them to `w`
+/// using the zigzag encoding. See `leb128::write::zigzag`.
+pub fn sleb_to_zigzag<R, W>(r: &mut R, w: &mut W, count: usize) -> Result<(), read::Error>
+    where R: ?Sized + io::Read,
+          W: ?Sized + io::Write
+{
+    for _ in 0..count {
+        let val = read::signed(r)?;
+        write::zigzag(w, val)?;
+    }
+    Ok(())
+}
+
+/// Read `count` zigzag encoded numbers from `r` and write each of them to `w`
+/// using the signed LEB128 encoding. See `leb128::read::zigzag`.
+pub fn zigzag_to_sleb<R, W>(r: &mut R, w: &mut W, count: usize) -> Result<(), read::Error>
+    where R: ?Sized + io::Read,
+          W: ?Sized + io::Write
+{
+    for _ in 0..count {
+        let val = read::zigzag(r)?;
+        write::signed(w, val)?;
+    }
+    Ok(())
+}
+
+/// Read `count` little-endian `u32`s from `r` and write each of them to `w`
+/// using the unsigned LEB128 encoding. Returns the number of bytes written to
+/// `w`.
+pub fn u32le_to_uleb<R, W>(r: &mut R, w: &mut W, count: usize) -> Result<usize, read::Error>
+    where R: ?Sized + io::Read,
+          W: ?Sized + io::Write
+{
+    let mut bytes_written = 0;
+    for _ in 0..count {
+        let mut buf = [0; 4];
+        r.read_exact(&mut buf)?;
+        bytes_written += write::unsigned(w, u32::from_le_bytes(buf) as u64)?;
+    }
+    Ok(bytes_written)
+}
+
+/// Read `count` unsigned LEB128 numbers from `r` and write each of them to `w`
+/// as a little-endian `u32`. Returns the number of bytes written to `w`, or an
+/// `Error::Overflow` if a number does not fit in 32 bits.
+pub fn uleb_to_u32le<R, W>(r: &mut R, w: &mut W, count: usize) -> Result<usize, read::Error>
+    where R: ?Sized + io::Read,
+          W: ?Sized + io::Write
+{
+    for _ in 0..count {
+        let val: u32 = read::unsigned_as(r)?;
+        w.write_all(&val.to_le_bytes())?;
+    }
+    Ok(count * 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let vals = [0, 1, -1, 63, -64, 64, -65, i64::MAX, i64::MIN];
+
+        let mut sleb = vec![];
+        for &val in &vals {
+            write::signed(&mut sleb, val).expect("Should write number");
+        }
+
+        let mut zigzag = vec![];
+        sleb_to_zigzag(&mut &sleb[..], &mut zigzag, vals.len()).expect("Should transform");
+
+        let mut readable = &zigzag[..];
+        for &val in &vals {
+            assert_eq!(read::zigzag(&mut readable).expect("Should read number"), val);
+        }
+        assert!(readable.is_empty());
+
+        let mut back = vec![];
+        zigzag_to_sleb(&mut &zigzag[..], &mut back, vals.len()).expect("Should transform");
+        assert_eq!(back, sleb);
+    }
+
+    #[test]
+    fn test_not_enough_data() {
+        let mut out = vec![];
+        match sleb_to_zigzag(&mut &[1u8][..], &mut out, 2) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+        assert_eq!(out, [2]);
+    }
+
+    #[test]
+    fn test_u32le_round_trip() {
+        let vals = [0u32, 1, 127, 128, 12857, u32::MAX];
+
+        let mut fixed = vec![];
+        for &val in &vals {
+            fixed.extend_from_slice(&val.to_le_bytes());
+        }
+
+        let mut uleb = vec![];
+        assert_eq!(u32le_to_uleb(&mut &fixed[..], &mut uleb, vals.len())
+                       .expect("Should transform"),
+                   uleb.len());
+        assert_eq!(uleb.len(), 1 + 1 + 1 + 2 + 2 + 5);
+
+        let mut readable = &uleb[..];
+        for &val in &vals {
+            assert_eq!(read::unsigned(&mut readable).expect("Should read number"),
+                       val as u64);
+        }
+        assert!(readable.is_empty());
+
+        let mut back = vec![];
+        assert_eq!(uleb_to_u32le(&mut &uleb[..], &mut back, vals.len())
+                       .expect("Should transform"),
+                   fixed.len());
+        assert_eq!(back, fixed);
+    }
+
+    #[test]
+    fn test_uleb_to_u32le_overflow() {
+        let mut uleb = vec![];
+        write::unsigned(&mut uleb, 1 << 32).expect("Should write number");
+        match uleb_to_u32le(&mut &uleb[..], &mut vec![], 1) {
+            Err(read::Error::Overflow) => {}
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+
+        match u32le_to_uleb(&mut &[1u8, 0, 0][..], &mut vec![], 1) {
+            Err(read::Error::IoError(e)) => assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof),
+            otherwise => panic!("Unexpected: {:?}", otherwise),
+        }
+    }
+}
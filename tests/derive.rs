@@ -0,0 +1,57 @@
+#![cfg(feature = "derive")]
+
+extern crate leb128;
+
+use leb128::Leb128;
+
+#[derive(Debug, PartialEq, Leb128)]
+struct Header {
+    version: u8,
+    length: u64,
+    offset: i32,
+    #[leb128(zigzag)]
+    delta: i64,
+}
+
+#[derive(Debug, PartialEq, Leb128)]
+struct Pair(u32, #[leb128(zigzag)] i16);
+
+#[test]
+fn can_round_trip_named_fields() {
+    let header = Header {
+        version: 4,
+        length: 300,
+        offset: -2,
+        delta: -1,
+    };
+
+    let mut buf = vec![];
+    assert_eq!(header.encode(&mut buf).expect("Should encode struct"), 5);
+    assert_eq!(buf, [4, 0xac, 0x02, 0x7e, 0x01]);
+
+    let decoded = Header::decode(&mut &buf[..]).expect("Should decode struct");
+    assert_eq!(decoded, header);
+}
+
+#[test]
+fn can_round_trip_tuple_fields() {
+    let pair = Pair(u32::MAX, i16::MIN);
+
+    let mut buf = vec![];
+    pair.encode(&mut buf).expect("Should encode struct");
+    assert_eq!(Pair::decode(&mut &buf[..]).expect("Should decode struct"), pair);
+}
+
+#[test]
+fn decode_rejects_out_of_range_fields() {
+    let mut buf = vec![];
+    leb128::write::unsigned(&mut buf, 256).expect("Should write number");
+    leb128::write::unsigned(&mut buf, 0).expect("Should write number");
+    leb128::write::signed(&mut buf, 0).expect("Should write number");
+    leb128::write::zigzag(&mut buf, 0).expect("Should write number");
+
+    match Header::decode(&mut &buf[..]) {
+        Err(leb128::read::Error::Overflow) => {}
+        otherwise => panic!("Unexpected: {:?}", otherwise),
+    }
+}
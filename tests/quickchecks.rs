@@ -19,7 +19,7 @@ fn can_round_trip_any_unsigned_int() {
         let mut v = vec![];
         leb128::write::unsigned(&mut v, x)?;
         let y = leb128::read::unsigned(&mut &v[..])
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            .map_err(io::Error::other)?;
         Ok(x == y)
     }
     quickcheck::quickcheck(f as fn(u64) -> io::Result<bool>);
@@ -41,8 +41,61 @@ fn can_round_trip_any_signed_int() {
         let mut v = vec![];
         leb128::write::signed(&mut v, x)?;
         let y = leb128::read::signed(&mut &v[..])
-            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+            .map_err(io::Error::other)?;
         Ok(x == y)
     }
     quickcheck::quickcheck(f as fn(i64) -> io::Result<bool>);
 }
+
+#[test]
+fn unsigned_matches_reference_encoding() {
+    fn f(x: u64) -> io::Result<bool> {
+        let mut v = vec![];
+        leb128::write::unsigned(&mut v, x)?;
+        Ok(v == leb128::reference::encode_unsigned(x))
+    }
+    quickcheck::quickcheck(f as fn(u64) -> io::Result<bool>);
+}
+
+#[test]
+fn signed_matches_reference_encoding() {
+    fn f(x: i64) -> io::Result<bool> {
+        let mut v = vec![];
+        leb128::write::signed(&mut v, x)?;
+        Ok(v == leb128::reference::encode_signed(x))
+    }
+    quickcheck::quickcheck(f as fn(i64) -> io::Result<bool>);
+}
+
+#[test]
+fn compat_matches_current_api() {
+    fn f(x: u64, y: i64) -> io::Result<bool> {
+        let mut v = vec![];
+        leb128::compat::write::unsigned(&mut v, x)?;
+        leb128::compat::write::signed(&mut v, y)?;
+
+        let mut w = vec![];
+        leb128::write::unsigned(&mut w, x)?;
+        leb128::write::signed(&mut w, y)?;
+
+        let mut r = &v[..];
+        let a = leb128::compat::read::unsigned(&mut r).map_err(io::Error::other)?;
+        let b = leb128::compat::read::signed(&mut r).map_err(io::Error::other)?;
+        Ok(v == w && a == x && b == y)
+    }
+    quickcheck::quickcheck(f as fn(u64, i64) -> io::Result<bool>);
+}
+
+#[test]
+fn signed_encoding_is_canonical() {
+    fn f(x: i64) -> io::Result<bool> {
+        let mut v = vec![];
+        leb128::write::signed(&mut v, x)?;
+        let y = leb128::read::Options::new()
+            .strict(true)
+            .signed(&mut &v[..])
+            .map_err(io::Error::other)?;
+        Ok(x == y && v.len() == leb128::write::signed_len(x))
+    }
+    quickcheck::quickcheck(f as fn(i64) -> io::Result<bool>);
+}